@@ -0,0 +1,165 @@
+//! M-of-N multisig administration
+//!
+//! Initialization issues N admin tokens and stores a threshold M. An admin
+//! opcode is authorized when at least M distinct admin tokens arrive in
+//! `incoming_alkanes` of the same call, or when enough approvals have been
+//! gathered across transactions for the call's proposal id.
+
+use crate::alkane_id_from_bytes;
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use alkanes_support::context::Context;
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use bitcoin::hashes::{sha256, Hash};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// Maximum number of admin tokens issued at initialization
+pub const MAX_ADMINS: u128 = 16;
+
+/// Derive the proposal id of an admin call from its full inputs (opcode first)
+///
+/// The id is the first 16 bytes of the SHA-256 of the inputs encoded as
+/// consecutive little-endian u128 values, read back as a little-endian u128.
+pub fn proposal_id(inputs: &[u128]) -> u128 {
+    let mut bytes = Vec::with_capacity(inputs.len() * 16);
+    for input in inputs {
+        bytes.extend_from_slice(&input.to_le_bytes());
+    }
+    let hash = sha256::Hash::hash(&bytes).to_byte_array();
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&hash[0..16]);
+    u128::from_le_bytes(id)
+}
+
+/// MultisigAdmin trait provides admin token bookkeeping and authorization
+pub trait MultisigAdmin: AlkaneResponder {
+    /// Get the pointer to the admin token list
+    fn admins_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/admins")
+    }
+
+    /// Get the registered admin token ids
+    fn admin_tokens(&self) -> Vec<AlkaneId> {
+        let pointer = self.admins_pointer();
+        (0..pointer.length())
+            .filter_map(|i| alkane_id_from_bytes(&pointer.select_index(i).get()).ok())
+            .collect()
+    }
+
+    /// Register an admin token id
+    fn add_admin_token(&self, id: &AlkaneId) {
+        let bytes: Vec<u8> = id.clone().into();
+        self.admins_pointer().append(Arc::new(bytes));
+    }
+
+    /// Get the pointer to the approval threshold
+    fn admin_threshold_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/admin-threshold")
+    }
+
+    /// Get the number of admin tokens required to authorize an admin call
+    fn admin_threshold(&self) -> u128 {
+        self.admin_threshold_pointer().get_value::<u128>().max(1)
+    }
+
+    /// Set the number of admin tokens required to authorize an admin call
+    fn set_admin_threshold(&self, v: u128) {
+        self.admin_threshold_pointer().set_value::<u128>(v);
+    }
+
+    /// Get the pointer to a proposal's approval count
+    fn proposal_pointer(&self, proposal_id: u128) -> StoragePointer {
+        StoragePointer::from_keyword("/proposals/").select(&proposal_id.to_le_bytes().to_vec())
+    }
+
+    /// Get the pointer to one admin's approval of a proposal
+    fn approval_pointer(&self, proposal_id: u128, admin: u32) -> StoragePointer {
+        StoragePointer::from_keyword("/approvals/")
+            .select(&proposal_id.to_le_bytes().to_vec())
+            .select_value::<u32>(admin)
+    }
+
+    /// Get the indices of the admins that approved a proposal
+    fn approvals(&self, proposal_id: u128) -> Vec<u32> {
+        (0..self.admins_pointer().length())
+            .filter(|i| self.approval_pointer(proposal_id, *i).get_value::<u8>() == 1)
+            .collect()
+    }
+
+    /// Record an admin's approval of a proposal
+    fn record_approval(&self, proposal_id: u128, admin: u32) -> Result<()> {
+        if admin >= self.admins_pointer().length() {
+            return Err(anyhow!("unknown admin index {}", admin));
+        }
+        let mut approval = self.approval_pointer(proposal_id, admin);
+        if approval.get_value::<u8>() == 1 {
+            return Ok(());
+        }
+        approval.set_value::<u8>(0x01);
+        let mut proposal = self.proposal_pointer(proposal_id);
+        let count = proposal.get_value::<u128>();
+        proposal.set_value::<u128>(count + 1);
+        Ok(())
+    }
+
+    /// Drop all approvals of a proposal so they cannot be replayed
+    fn clear_proposal(&self, proposal_id: u128) {
+        for admin in self.approvals(proposal_id) {
+            self.approval_pointer(proposal_id, admin)
+                .set(Arc::new(Vec::new()));
+        }
+        self.proposal_pointer(proposal_id).set(Arc::new(Vec::new()));
+    }
+
+    /// Get the indices of the admin tokens present in the incoming alkanes
+    fn presented_admins(&self, context: &Context) -> Vec<u32> {
+        self.admin_tokens()
+            .iter()
+            .enumerate()
+            .filter(|(_, admin)| {
+                context
+                    .incoming_alkanes
+                    .0
+                    .iter()
+                    .any(|transfer| transfer.id == **admin && transfer.value > 0)
+            })
+            .map(|(i, _)| i as u32)
+            .collect()
+    }
+
+    /// Require M distinct admin tokens, counting both the tokens in this call
+    /// and the approvals stored for this call's proposal id
+    fn only_owner(&self, context: &Context) -> Result<()> {
+        if self.admins_pointer().length() == 0 {
+            return Err(anyhow!("no admin tokens registered"));
+        }
+        let threshold = self.admin_threshold();
+        let mut signers = self.presented_admins(context);
+        let proposal_id = proposal_id(&context.inputs);
+        if signers.len() as u128 >= threshold {
+            // Earlier approvals of the same call are spent along with it
+            self.clear_proposal(proposal_id);
+            return Ok(());
+        }
+
+        for admin in self.approvals(proposal_id) {
+            if !signers.contains(&admin) {
+                signers.push(admin);
+            }
+        }
+        if (signers.len() as u128) < threshold {
+            return Err(anyhow!(
+                "Admin approval required: {} of {} for proposal {}",
+                signers.len(),
+                threshold,
+                proposal_id
+            ));
+        }
+
+        // Approvals are consumed by the call they authorized
+        self.clear_proposal(proposal_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! Merkle-distributed airdrop claims
+//!
+//! The admins commit a merkle root of (recipient script, amount) leaves and
+//! fund the pool with this token. Recipients claim with a proof carried in
+//! the witness envelope of the claim transaction. Funds left after the
+//! expiry height can be reclaimed by the admins, and once a pool is empty,
+//! claimed out or reclaimed, a new root can be set.
+//!
+//! Leaves are `sha256(0x00 || script || amount as u128 LE)` and inner nodes
+//! are `sha256(0x01 || lower child || higher child)`, so proofs need no
+//! position bits. The prefixes keep a leaf preimage from ever passing as an
+//! inner node, which would let a 64-byte leaf stand in for a subtree.
+
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use anyhow::{anyhow, Result};
+use bitcoin::hashes::{sha256, Hash};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// Domain prefix of leaf preimages
+pub const LEAF_PREFIX: u8 = 0x00;
+/// Domain prefix of inner node preimages
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Hash of one (recipient script, amount) leaf
+pub fn airdrop_leaf(script: &[u8], amount: u128) -> [u8; 32] {
+    let mut bytes = vec![LEAF_PREFIX];
+    bytes.extend_from_slice(script);
+    bytes.extend_from_slice(&amount.to_le_bytes());
+    sha256::Hash::hash(&bytes).to_byte_array()
+}
+
+/// Hash two nodes in ascending byte order
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut bytes = Vec::with_capacity(65);
+    bytes.push(NODE_PREFIX);
+    if a <= b {
+        bytes.extend_from_slice(a);
+        bytes.extend_from_slice(b);
+    } else {
+        bytes.extend_from_slice(b);
+        bytes.extend_from_slice(a);
+    }
+    sha256::Hash::hash(&bytes).to_byte_array()
+}
+
+/// Check a proof given as concatenated 32-byte sibling hashes, leaf first
+pub fn verify_proof(root: &[u8; 32], leaf: &[u8; 32], proof: &[u8]) -> bool {
+    if proof.len() % 32 != 0 {
+        return false;
+    }
+    let computed = proof.chunks(32).fold(*leaf, |node, sibling| {
+        let mut sibling_bytes = [0u8; 32];
+        sibling_bytes.copy_from_slice(sibling);
+        hash_pair(&node, &sibling_bytes)
+    });
+    &computed == root
+}
+
+/// Build a 32-byte root from its opcode input form; `root_lo` holds bytes
+/// 0..16 and `root_hi` bytes 16..32, each as a little-endian u128
+pub fn root_from_parts(root_hi: u128, root_lo: u128) -> [u8; 32] {
+    let mut root = [0u8; 32];
+    root[0..16].copy_from_slice(&root_lo.to_le_bytes());
+    root[16..32].copy_from_slice(&root_hi.to_le_bytes());
+    root
+}
+
+/// Airdrop trait provides the pool and claim bookkeeping
+pub trait Airdrop: AlkaneResponder {
+    /// Get the pointer to the merkle root
+    fn airdrop_root_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/airdrop/root")
+    }
+
+    /// Get the merkle root, if an airdrop has been set
+    fn airdrop_root(&self) -> Option<[u8; 32]> {
+        self.airdrop_root_pointer().get().as_slice().try_into().ok()
+    }
+
+    /// Get the pointer to the expiry height
+    fn airdrop_expiry_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/airdrop/expiry")
+    }
+
+    /// Get the height after which unclaimed funds can be reclaimed
+    fn airdrop_expiry(&self) -> u64 {
+        self.airdrop_expiry_pointer().get_value::<u64>()
+    }
+
+    /// Get the pointer to the unclaimed pool
+    fn airdrop_remaining_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/airdrop/remaining")
+    }
+
+    /// Get the unclaimed pool
+    fn airdrop_remaining(&self) -> u128 {
+        self.airdrop_remaining_pointer().get_value::<u128>()
+    }
+
+    /// Set the root, expiry and pool of a new airdrop once the last pool is empty
+    fn open_airdrop(&self, root: [u8; 32], expiry: u64, funding: u128) -> Result<()> {
+        if self.airdrop_root().is_some() && self.airdrop_remaining() > 0 {
+            return Err(anyhow!(
+                "airdrop still holds {} unclaimed tokens",
+                self.airdrop_remaining()
+            ));
+        }
+        self.airdrop_root_pointer().set(Arc::new(root.to_vec()));
+        self.airdrop_expiry_pointer().set_value::<u64>(expiry);
+        self.airdrop_remaining_pointer().set_value::<u128>(funding);
+        Ok(())
+    }
+
+    /// Get the pointer to a leaf's claimed marker under a root
+    fn airdrop_claimed_pointer(&self, root: &[u8; 32], leaf: &[u8; 32]) -> StoragePointer {
+        StoragePointer::from_keyword("/airdrop/claimed/")
+            .select(&root.to_vec())
+            .select(&leaf.to_vec())
+    }
+
+    /// Check whether a leaf of a root has been claimed
+    fn is_airdrop_claimed(&self, root: &[u8; 32], leaf: &[u8; 32]) -> bool {
+        self.airdrop_claimed_pointer(root, leaf).get_value::<u8>() == 1
+    }
+
+    /// Verify and record a claim, returning the amount to pay
+    fn claim_airdrop_leaf(
+        &self,
+        script: &[u8],
+        amount: u128,
+        proof: &[u8],
+        height: u64,
+    ) -> Result<u128> {
+        let root = self.airdrop_root().ok_or_else(|| anyhow!("no airdrop set"))?;
+        if height > self.airdrop_expiry() {
+            return Err(anyhow!("airdrop expired at height {}", self.airdrop_expiry()));
+        }
+        let leaf = airdrop_leaf(script, amount);
+        if self.is_airdrop_claimed(&root, &leaf) {
+            return Err(anyhow!("airdrop already claimed"));
+        }
+        if !verify_proof(&root, &leaf, proof) {
+            return Err(anyhow!("invalid airdrop proof"));
+        }
+        let remaining = self
+            .airdrop_remaining()
+            .checked_sub(amount)
+            .ok_or_else(|| anyhow!("airdrop pool exhausted"))?;
+        self.airdrop_remaining_pointer().set_value::<u128>(remaining);
+        self.airdrop_claimed_pointer(&root, &leaf).set_value::<u8>(0x01);
+        Ok(amount)
+    }
+
+    /// Empty the pool after expiry, returning the unclaimed amount
+    fn reclaim_airdrop_pool(&self, height: u64) -> Result<u128> {
+        if self.airdrop_root().is_none() {
+            return Err(anyhow!("no airdrop set"));
+        }
+        if height <= self.airdrop_expiry() {
+            return Err(anyhow!(
+                "airdrop claimable until height {}",
+                self.airdrop_expiry()
+            ));
+        }
+        let remaining = self.airdrop_remaining();
+        self.airdrop_remaining_pointer().set_value::<u128>(0);
+        Ok(remaining)
+    }
+}
@@ -0,0 +1,86 @@
+//! Per-height checkpoints of a u128 value
+//!
+//! A checkpoint is written for every height at which the value changes, so
+//! the value at any past height can be found by binary search.
+
+use alkanes_runtime::storage::StoragePointer;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// Encoded size: height (u64) + value (u128)
+const CHECKPOINT_LEN: usize = 24;
+
+/// Append-only list of (height, value) checkpoints in height order
+pub struct CheckpointList(pub StoragePointer);
+
+impl CheckpointList {
+    /// Open the checkpoint list stored under `keyword`
+    pub fn from_keyword(keyword: &str) -> Self {
+        Self(StoragePointer::from_keyword(keyword))
+    }
+
+    /// Get the number of checkpoints
+    pub fn len(&self) -> u32 {
+        self.0.length()
+    }
+
+    /// Check whether no checkpoint has been written
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the checkpoint at position `i`
+    pub fn get(&self, i: u32) -> Result<(u64, u128)> {
+        let bytes = self.0.select_index(i).get();
+        if bytes.len() != CHECKPOINT_LEN {
+            return Err(anyhow!("invalid checkpoint {}", i));
+        }
+        Ok((
+            u64::from_le_bytes(bytes[0..8].try_into()?),
+            u128::from_le_bytes(bytes[8..24].try_into()?),
+        ))
+    }
+
+    /// Record `value` at `height`, replacing a checkpoint already written at that height
+    pub fn record(&self, height: u64, value: u128) -> Result<()> {
+        let mut bytes = height.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&value.to_le_bytes());
+        let len = self.len();
+        if len > 0 {
+            let (last_height, _) = self.get(len - 1)?;
+            if height < last_height {
+                return Err(anyhow!(
+                    "checkpoint height {} is before {}",
+                    height,
+                    last_height
+                ));
+            }
+            if height == last_height {
+                self.0.select_index(len - 1).set(Arc::new(bytes));
+                return Ok(());
+            }
+        }
+        self.0.append(Arc::new(bytes));
+        Ok(())
+    }
+
+    /// Get the value as of `height`, 0 before the first checkpoint
+    pub fn value_at(&self, height: u64) -> Result<u128> {
+        // Find the first checkpoint above `height`; the one before it applies
+        let (mut low, mut high) = (0u32, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.get(mid)?.0 <= height {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == 0 {
+            Ok(0)
+        } else {
+            Ok(self.get(low - 1)?.1)
+        }
+    }
+}
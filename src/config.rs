@@ -0,0 +1,224 @@
+//! Extended initialization config
+//!
+//! Options that don't fit the fixed Initialize inputs can be carried in a
+//! config record, inscribed as its own envelope in front of the data
+//! envelope. The record is self-describing:
+//!
+//! ```text
+//! magic    "FMCF"
+//! version  u8
+//! entries  tag u8 + length u16 + value, repeated, each tag at most once
+//! ```
+//!
+//! All integers are little-endian. Tags with the high bit set are optional:
+//! readers that don't know them skip them, while unknown tags without it are
+//! rejected. Values set in the record take precedence over the matching
+//! trailing Initialize inputs.
+
+use crate::codec::Reader;
+use crate::data::envelope_payloads;
+use anyhow::{anyhow, Result};
+use bitcoin::Transaction;
+
+/// Marker opening every config record
+pub const CONFIG_MAGIC: &[u8; 4] = b"FMCF";
+
+/// Current version of the config record
+pub const CONFIG_VERSION: u8 = 1;
+
+/// Bit marking a tag that readers may skip when they don't know it
+pub const TAG_OPTIONAL: u8 = 0x80;
+
+/// Number of admin tokens to issue (u8)
+pub const TAG_ADMIN_COUNT: u8 = 1;
+/// Admin tokens required to authorize an admin call (u8)
+pub const TAG_ADMIN_THRESHOLD: u8 = 2;
+/// Timelock delay in blocks (u64)
+pub const TAG_TIMELOCK_DELAY: u8 = 3;
+/// Display decimals (u8)
+pub const TAG_DECIMALS: u8 = 4;
+/// MIME type of the data segment (printable ASCII)
+pub const TAG_CONTENT_TYPE: u8 = 5;
+/// Total size of a multi-transaction data upload (u64)
+pub const TAG_DATA_SIZE: u8 = 6;
+/// SHA-256 of the complete uploaded data segment (32 bytes)
+pub const TAG_DATA_HASH: u8 = 7;
+/// Token name (UTF-8)
+pub const TAG_NAME: u8 = 8;
+/// Token symbol (UTF-8)
+pub const TAG_SYMBOL: u8 = 9;
+/// Transaction input holding the data envelope (u32)
+pub const TAG_DATA_INPUT: u8 = 10;
+/// Largest envelope accepted as data in bytes (u64)
+pub const TAG_MAX_DATA_LEN: u8 = 11;
+/// Start in the Draft launch state instead of Live (u8, 0 or 1)
+pub const TAG_DRAFT: u8 = 12;
+
+/// Options read from a config record; unset options fall back to the
+/// trailing Initialize inputs and then to their defaults
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InitConfig {
+    pub admin_count: Option<u8>,
+    pub admin_threshold: Option<u8>,
+    pub timelock_delay: Option<u64>,
+    pub decimals: Option<u8>,
+    pub content_type: Option<String>,
+    pub data_size: Option<u64>,
+    pub data_hash: Option<[u8; 32]>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub data_input: Option<u32>,
+    pub max_data_len: Option<u64>,
+    pub draft: bool,
+}
+
+/// Check for the config record marker
+pub fn is_config(payload: &[u8]) -> bool {
+    payload.starts_with(CONFIG_MAGIC)
+}
+
+/// Position of the config record among a transaction's envelope payloads
+///
+/// The record is always the first envelope, and only when it carries the
+/// marker and another envelope follows it. A lone envelope is therefore
+/// always data, even when the data itself starts with the marker.
+pub fn config_position(payloads: &[(u32, Vec<u8>)]) -> Option<usize> {
+    match payloads {
+        [(_, first), _, ..] if is_config(first) => Some(0),
+        _ => None,
+    }
+}
+
+/// Payload of the config envelope in the transaction, see `config_position`
+pub fn config_payload(tx: &Transaction) -> Option<Vec<u8>> {
+    let payloads = envelope_payloads(tx);
+    config_position(&payloads).map(|i| payloads[i].1.clone())
+}
+
+/// Append one entry
+fn put_entry(bytes: &mut Vec<u8>, tag: u8, value: &[u8]) {
+    bytes.push(tag);
+    bytes.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(value);
+}
+
+/// Read a fixed-size value
+fn fixed<const N: usize>(tag: u8, value: &[u8]) -> Result<[u8; N]> {
+    value
+        .try_into()
+        .map_err(|_| anyhow!("config tag {} expects {} bytes, got {}", tag, N, value.len()))
+}
+
+/// Read a UTF-8 string value
+fn utf8(tag: u8, value: &[u8]) -> Result<String> {
+    String::from_utf8(value.to_vec()).map_err(|_| anyhow!("config tag {} is not valid UTF-8", tag))
+}
+
+impl InitConfig {
+    /// Encode as a config record, ready to be inscribed as an envelope
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        for s in [&self.content_type, &self.name, &self.symbol].into_iter().flatten() {
+            if s.len() > u16::MAX as usize {
+                return Err(anyhow!("config value of {} bytes is too long", s.len()));
+            }
+        }
+        let mut bytes = CONFIG_MAGIC.to_vec();
+        bytes.push(CONFIG_VERSION);
+        if let Some(v) = self.admin_count {
+            put_entry(&mut bytes, TAG_ADMIN_COUNT, &[v]);
+        }
+        if let Some(v) = self.admin_threshold {
+            put_entry(&mut bytes, TAG_ADMIN_THRESHOLD, &[v]);
+        }
+        if let Some(v) = self.timelock_delay {
+            put_entry(&mut bytes, TAG_TIMELOCK_DELAY, &v.to_le_bytes());
+        }
+        if let Some(v) = self.decimals {
+            put_entry(&mut bytes, TAG_DECIMALS, &[v]);
+        }
+        if let Some(v) = &self.content_type {
+            put_entry(&mut bytes, TAG_CONTENT_TYPE, v.as_bytes());
+        }
+        if let Some(v) = self.data_size {
+            put_entry(&mut bytes, TAG_DATA_SIZE, &v.to_le_bytes());
+        }
+        if let Some(v) = &self.data_hash {
+            put_entry(&mut bytes, TAG_DATA_HASH, v);
+        }
+        if let Some(v) = &self.name {
+            put_entry(&mut bytes, TAG_NAME, v.as_bytes());
+        }
+        if let Some(v) = &self.symbol {
+            put_entry(&mut bytes, TAG_SYMBOL, v.as_bytes());
+        }
+        if let Some(v) = self.data_input {
+            put_entry(&mut bytes, TAG_DATA_INPUT, &v.to_le_bytes());
+        }
+        if let Some(v) = self.max_data_len {
+            put_entry(&mut bytes, TAG_MAX_DATA_LEN, &v.to_le_bytes());
+        }
+        if self.draft {
+            put_entry(&mut bytes, TAG_DRAFT, &[1]);
+        }
+        Ok(bytes)
+    }
+
+    /// Decode and validate a config record
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(CONFIG_MAGIC.len())? != CONFIG_MAGIC {
+            return Err(anyhow!("not a config record"));
+        }
+        let version = reader.u8()?;
+        if version != CONFIG_VERSION {
+            return Err(anyhow!("unsupported config version {}", version));
+        }
+
+        let mut config = Self::default();
+        let mut seen = [false; 256];
+        while !reader.is_empty() {
+            let tag = reader.u8()?;
+            let len = u16::from_le_bytes(fixed(tag, reader.take(2)?)?) as usize;
+            let value = reader.take(len)?;
+            if seen[tag as usize] {
+                return Err(anyhow!("config tag {} appears twice", tag));
+            }
+            seen[tag as usize] = true;
+            match tag {
+                TAG_ADMIN_COUNT => config.admin_count = Some(fixed::<1>(tag, value)?[0]),
+                TAG_ADMIN_THRESHOLD => config.admin_threshold = Some(fixed::<1>(tag, value)?[0]),
+                TAG_TIMELOCK_DELAY => {
+                    config.timelock_delay = Some(u64::from_le_bytes(fixed(tag, value)?))
+                }
+                TAG_DECIMALS => config.decimals = Some(fixed::<1>(tag, value)?[0]),
+                TAG_CONTENT_TYPE => {
+                    if value.is_empty() || !value.iter().all(|b| b.is_ascii_graphic()) {
+                        return Err(anyhow!("content type must be printable ASCII"));
+                    }
+                    config.content_type = Some(utf8(tag, value)?)
+                }
+                TAG_DATA_SIZE => config.data_size = Some(u64::from_le_bytes(fixed(tag, value)?)),
+                TAG_DATA_HASH => config.data_hash = Some(fixed(tag, value)?),
+                TAG_NAME => config.name = Some(utf8(tag, value)?),
+                TAG_SYMBOL => config.symbol = Some(utf8(tag, value)?),
+                TAG_DATA_INPUT => config.data_input = Some(u32::from_le_bytes(fixed(tag, value)?)),
+                TAG_MAX_DATA_LEN => {
+                    config.max_data_len = Some(u64::from_le_bytes(fixed(tag, value)?))
+                }
+                TAG_DRAFT => {
+                    config.draft = match fixed::<1>(tag, value)?[0] {
+                        0 => false,
+                        1 => true,
+                        v => return Err(anyhow!("config draft flag must be 0 or 1, got {}", v)),
+                    }
+                }
+                tag if tag & TAG_OPTIONAL != 0 => {}
+                tag => return Err(anyhow!("unknown config tag {}", tag)),
+            }
+        }
+        if config.data_size.is_some() != config.data_hash.is_some() {
+            return Err(anyhow!("config data size and hash must be given together"));
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,306 @@
+//! Data segment metadata
+//!
+//! Alkanes envelopes carry only a payload, with no content-type field, so the
+//! MIME type comes from the Initialize input when given and is otherwise
+//! sniffed from the leading bytes of the uncompressed data.
+
+use crate::codec::Reader;
+use crate::config::config_position;
+use alkanes_support::envelope::RawEnvelope;
+use anyhow::{anyhow, Result};
+use bitcoin::hashes::Hash;
+use bitcoin::{Transaction, Txid};
+
+/// Current version of the `DataInfo` layout
+pub const DATA_INFO_VERSION: u8 = 1;
+
+/// Longest URI accepted as a data reference
+pub const MAX_URI_LEN: usize = 1024;
+
+/// Maximum number of bytes returned by one `GetDataRange` call
+pub const MAX_DATA_RANGE: u128 = 64 * 1024;
+
+/// Encoding of gzip-compressed data
+pub const ENCODING_GZIP: &str = "gzip";
+/// Encoding of data stored as-is
+pub const ENCODING_IDENTITY: &str = "identity";
+
+/// Check for the gzip magic bytes
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.len() >= 2 && bytes[0] == 0x1f && bytes[1] == 0x8b
+}
+
+/// Guess the MIME type of uncompressed data from its leading bytes
+pub fn sniff_content_type(bytes: &[u8]) -> &'static str {
+    let head = &bytes[..bytes.len().min(512)];
+    let text = String::from_utf8_lossy(head).trim_start().to_ascii_lowercase();
+    if bytes.is_empty() {
+        ""
+    } else if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+        "image/gif"
+    } else if head.starts_with(&[0xff, 0xd8, 0xff]) {
+        "image/jpeg"
+    } else if head.len() >= 12 && &head[0..4] == b"RIFF" && &head[8..12] == b"WEBP" {
+        "image/webp"
+    } else if text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")) {
+        "image/svg+xml"
+    } else if text.starts_with("<!doctype html") || text.starts_with("<html") {
+        "text/html"
+    } else if text.starts_with('{') || text.starts_with('[') {
+        "application/json"
+    } else if std::str::from_utf8(bytes).is_ok() {
+        "text/plain"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// Payloads of every envelope in the transaction, with the input each is in
+///
+/// The leading push of each envelope is skipped, as `find_witness_payload` does.
+pub fn envelope_payloads(tx: &Transaction) -> Vec<(u32, Vec<u8>)> {
+    RawEnvelope::from_transaction(tx)
+        .into_iter()
+        .map(|envelope| {
+            let payload: Vec<u8> = envelope.payload.into_iter().skip(1).flatten().collect();
+            (envelope.input, payload)
+        })
+        .collect()
+}
+
+/// Payload of the first non-empty envelope in the transaction, or only in
+/// input `input` when given, passing over the config record
+///
+/// Only the envelope at the config position is passed over, so data that
+/// happens to start with the config marker is still served as data.
+pub fn envelope_payload(tx: &Transaction, input: Option<u32>) -> Option<Vec<u8>> {
+    let payloads = envelope_payloads(tx);
+    let config = config_position(&payloads);
+    payloads
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != config)
+        .map(|(_, envelope)| envelope)
+        .filter(|(envelope_input, _)| input.is_none() || input == Some(*envelope_input))
+        .map(|(_, payload)| payload)
+        .find(|payload| !payload.is_empty())
+}
+
+/// Decode a MIME type packed little-endian into Initialize input words
+pub fn content_type_from_words(words: &[u128]) -> Result<String> {
+    let content_type = crate::packing::decode_bytes(words);
+    if content_type.is_empty() || !content_type.iter().all(|b| b.is_ascii_graphic()) {
+        return Err(anyhow!("content type must be printable ASCII"));
+    }
+    Ok(String::from_utf8(content_type)?)
+}
+
+/// A data segment still being uploaded across transactions
+///
+/// The declared size and hash cover the segment as stored, so for gzip data
+/// they are those of the compressed bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataUpload {
+    /// Size the complete segment must reach
+    pub size: u64,
+    /// SHA-256 the complete segment must match
+    pub sha256: [u8; 32],
+    /// Content type given at initialization, empty to sniff once sealed
+    pub content_type: String,
+}
+
+impl DataUpload {
+    /// Encode as size (u64 LE), hash and a u32 length-prefixed content type
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = self.size.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&self.sha256);
+        bytes.extend_from_slice(&(self.content_type.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(self.content_type.as_bytes());
+        bytes
+    }
+
+    /// Decode from the layout produced by `encode`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        Ok(Self {
+            size: reader.u64()?,
+            sha256: reader.take(32)?.try_into()?,
+            content_type: reader.string()?,
+        })
+    }
+}
+
+/// Description of the stored data segment, served by `GetDataInfo`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataInfo {
+    /// MIME type of the uncompressed data
+    pub content_type: String,
+    /// `ENCODING_GZIP` or `ENCODING_IDENTITY`
+    pub encoding: String,
+    /// Size of the data as stored
+    pub stored_len: u64,
+    /// Size of the data once decompressed
+    pub plain_len: u64,
+    /// SHA-256 of the data as stored, the same hash `DataCommitment` holds
+    pub sha256: [u8; 32],
+}
+
+impl DataInfo {
+    /// Encode as version, two u32 length-prefixed strings, the two sizes
+    /// (u64 LE) and the hash
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![DATA_INFO_VERSION];
+        for s in [&self.content_type, &self.encoding] {
+            bytes.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(s.as_bytes());
+        }
+        bytes.extend_from_slice(&self.stored_len.to_le_bytes());
+        bytes.extend_from_slice(&self.plain_len.to_le_bytes());
+        bytes.extend_from_slice(&self.sha256);
+        bytes
+    }
+
+    /// Decode a `GetDataInfo` response
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let version = reader.u8()?;
+        if version != DATA_INFO_VERSION {
+            return Err(anyhow!("unsupported data info version {}", version));
+        }
+        Ok(Self {
+            content_type: reader.string()?,
+            encoding: reader.string()?,
+            stored_len: reader.u64()?,
+            plain_len: reader.u64()?,
+            sha256: reader.take(32)?.try_into()?,
+        })
+    }
+}
+
+/// Hash of the data segment as committed by one transaction
+///
+/// The hash covers the segment as stored, so for gzip data it is that of
+/// the compressed bytes, which can be checked against the inscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataCommitment {
+    /// SHA-256 of the stored data segment
+    pub sha256: [u8; 32],
+    /// Transaction the data came from (the sealing transaction for uploads)
+    pub txid: Txid,
+    /// Block height of that transaction
+    pub height: u64,
+}
+
+impl DataCommitment {
+    /// Encoded size: hash (32) + txid (32) + height (u64)
+    pub const ENCODED_LEN: usize = 72;
+
+    /// Encode as hash, txid in internal byte order, then height (u64 LE)
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_LEN);
+        bytes.extend_from_slice(&self.sha256);
+        bytes.extend_from_slice(self.txid.as_byte_array());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes
+    }
+
+    /// Decode from the layout produced by `encode`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(anyhow!("invalid data commitment length {}", bytes.len()));
+        }
+        Ok(Self {
+            sha256: bytes[0..32].try_into()?,
+            txid: Txid::from_slice(&bytes[32..64])?,
+            height: u64::from_le_bytes(bytes[64..72].try_into()?),
+        })
+    }
+
+    /// Decode a `GetDataHashHistory` response, oldest first
+    pub fn decode_history(bytes: &[u8]) -> Result<Vec<Self>> {
+        if bytes.len() % Self::ENCODED_LEN != 0 {
+            return Err(anyhow!("invalid data hash history length {}", bytes.len()));
+        }
+        bytes
+            .chunks(Self::ENCODED_LEN)
+            .map(Self::decode)
+            .collect()
+    }
+}
+
+/// Location of token data kept outside the contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataReference {
+    /// An existing ordinals inscription
+    Inscription { txid: Txid, index: u32 },
+    /// Any URI, such as `ipfs://...` or `https://...`
+    Uri(String),
+}
+
+impl DataReference {
+    /// Check a URI reference: printable ASCII with a scheme, at most `MAX_URI_LEN` bytes
+    pub fn uri(bytes: Vec<u8>) -> Result<Self> {
+        if bytes.is_empty() || bytes.len() > MAX_URI_LEN {
+            return Err(anyhow!("URI must be 1 to {} bytes", MAX_URI_LEN));
+        }
+        if !bytes.iter().all(|b| b.is_ascii_graphic()) {
+            return Err(anyhow!("URI must be printable ASCII without spaces"));
+        }
+        let uri = String::from_utf8(bytes)?;
+        let scheme = uri.split(':').next().unwrap_or_default();
+        if scheme.is_empty()
+            || scheme.len() == uri.len()
+            || !scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        {
+            return Err(anyhow!("URI has no scheme"));
+        }
+        Ok(DataReference::Uri(uri))
+    }
+
+    /// Encode as a tag (0 inscription, 1 URI) followed by txid (internal
+    /// byte order) and index (u32 LE), or a u32 length-prefixed URI
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            DataReference::Inscription { txid, index } => {
+                let mut bytes = vec![0u8];
+                bytes.extend_from_slice(txid.as_byte_array());
+                bytes.extend_from_slice(&index.to_le_bytes());
+                bytes
+            }
+            DataReference::Uri(uri) => {
+                let mut bytes = vec![1u8];
+                bytes.extend_from_slice(&(uri.len() as u32).to_le_bytes());
+                bytes.extend_from_slice(uri.as_bytes());
+                bytes
+            }
+        }
+    }
+
+    /// Decode a `GetDataReference` response
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        Ok(match reader.u8()? {
+            0 => DataReference::Inscription {
+                txid: Txid::from_slice(reader.take(32)?)?,
+                index: reader.u32()?,
+            },
+            1 => DataReference::Uri(reader.string()?),
+            tag => return Err(anyhow!("unknown data reference tag {}", tag)),
+        })
+    }
+}
+
+impl std::fmt::Display for DataReference {
+    /// Inscriptions display as their inscription id (`<txid>i<index>`)
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DataReference::Inscription { txid, index } => write!(f, "{}i{}", txid, index),
+            DataReference::Uri(uri) => write!(f, "{}", uri),
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Structured event records for indexers
+//!
+//! State-changing opcodes append an event block to `CallResponse.data`, after
+//! any data the opcode already returns. The block ends with a fixed footer so
+//! it can be located from the end of the response:
+//!
+//! ```text
+//! events    tag u8 + fixed-size body, repeated
+//! block_len u32   length of the events section
+//! version   u8
+//! magic     "FMEV"
+//! ```
+//!
+//! All integers are little-endian and AlkaneIds are 32 bytes (block, tx).
+
+use crate::codec::Reader;
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+
+/// Current version of the event encoding
+pub const EVENT_VERSION: u8 = 1;
+
+/// Marker closing every event block
+pub const EVENT_MAGIC: &[u8; 4] = b"FMEV";
+
+/// Size of the footer: block_len (u32) + version (u8) + magic
+pub const EVENT_FOOTER_LEN: usize = 9;
+
+/// An event emitted by a state-changing opcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The contract was initialized
+    Initialized {
+        token_units: u128,
+        value_per_mint: u128,
+        cap: u128,
+    },
+    /// A mint succeeded; `index` is its zero-based position in the mint ledger
+    Minted { index: u128, amount: u128 },
+    /// Tokens were burned
+    Burned { amount: u128 },
+    /// A queued parameter change was applied
+    ParamsChanged { action: u128, value: u128 },
+    /// Minting was closed with the final mint count
+    Finalized { height: u64, minted: u128 },
+    /// A parameter change was queued
+    ChangeQueued {
+        change_id: u32,
+        action: u128,
+        value: u128,
+        eta: u64,
+    },
+    /// A queued parameter change was cancelled
+    ChangeCancelled { change_id: u32 },
+    /// An admin approved a proposal
+    Approved { proposal_id: u128, admin: u32 },
+    /// Alkanes were kept by the treasury
+    Deposited { id: AlkaneId, amount: u128 },
+    /// Alkanes were withdrawn from the treasury
+    Withdrawn { id: AlkaneId, amount: u128 },
+    /// An airdrop pool was funded
+    AirdropFunded { amount: u128, expiry: u64 },
+    /// An airdrop leaf was claimed
+    AirdropClaimed { amount: u128 },
+    /// Unclaimed airdrop funds were reclaimed
+    AirdropReclaimed { amount: u128 },
+    /// A chunk was appended to the data segment; `len` is the size so far
+    DataAppended { len: u64 },
+    /// The uploaded data segment was verified and locked
+    DataSealed { len: u64 },
+    /// A metadata attribute was set; `key` is the packed key
+    AttributeSet { key: u128 },
+    /// The metadata attributes were frozen
+    AttributesFrozen,
+    /// The token data was pointed at an inscription or URI
+    DataReferenceSet,
+    /// The launch state changed; `go_live` is the scheduled go-live height
+    StateChanged { state: u8, go_live: u64 },
+    /// A minter's allowance was set; 0 revokes it
+    MinterSet { id: AlkaneId, allowance: u128 },
+    /// An approved minter minted through `MintTo`
+    MintedTo { id: AlkaneId, amount: u128 },
+    /// The chunks of a pending data upload were dropped
+    DataUploadAborted,
+}
+
+impl Event {
+    /// Wire tag of the event
+    pub fn tag(&self) -> u8 {
+        match self {
+            Event::Initialized { .. } => 0,
+            Event::Minted { .. } => 1,
+            Event::Burned { .. } => 2,
+            Event::ParamsChanged { .. } => 3,
+            Event::Finalized { .. } => 4,
+            Event::ChangeQueued { .. } => 5,
+            Event::ChangeCancelled { .. } => 6,
+            Event::Approved { .. } => 7,
+            Event::Deposited { .. } => 8,
+            Event::Withdrawn { .. } => 9,
+            Event::AirdropFunded { .. } => 10,
+            Event::AirdropClaimed { .. } => 11,
+            Event::AirdropReclaimed { .. } => 12,
+            Event::DataAppended { .. } => 13,
+            Event::DataSealed { .. } => 14,
+            Event::AttributeSet { .. } => 15,
+            Event::AttributesFrozen => 16,
+            Event::DataReferenceSet => 17,
+            Event::StateChanged { .. } => 18,
+            Event::MinterSet { .. } => 19,
+            Event::MintedTo { .. } => 20,
+            Event::DataUploadAborted => 21,
+        }
+    }
+
+    /// Append the tag and body to `bytes`
+    pub fn encode_into(&self, bytes: &mut Vec<u8>) {
+        bytes.push(self.tag());
+        match self {
+            Event::Initialized {
+                token_units,
+                value_per_mint,
+                cap,
+            } => {
+                bytes.extend_from_slice(&token_units.to_le_bytes());
+                bytes.extend_from_slice(&value_per_mint.to_le_bytes());
+                bytes.extend_from_slice(&cap.to_le_bytes());
+            }
+            Event::Minted { index, amount } => {
+                bytes.extend_from_slice(&index.to_le_bytes());
+                bytes.extend_from_slice(&amount.to_le_bytes());
+            }
+            Event::Burned { amount } => {
+                bytes.extend_from_slice(&amount.to_le_bytes());
+            }
+            Event::ParamsChanged { action, value } => {
+                bytes.extend_from_slice(&action.to_le_bytes());
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            Event::Finalized { height, minted } => {
+                bytes.extend_from_slice(&height.to_le_bytes());
+                bytes.extend_from_slice(&minted.to_le_bytes());
+            }
+            Event::ChangeQueued {
+                change_id,
+                action,
+                value,
+                eta,
+            } => {
+                bytes.extend_from_slice(&change_id.to_le_bytes());
+                bytes.extend_from_slice(&action.to_le_bytes());
+                bytes.extend_from_slice(&value.to_le_bytes());
+                bytes.extend_from_slice(&eta.to_le_bytes());
+            }
+            Event::ChangeCancelled { change_id } => {
+                bytes.extend_from_slice(&change_id.to_le_bytes());
+            }
+            Event::Approved { proposal_id, admin } => {
+                bytes.extend_from_slice(&proposal_id.to_le_bytes());
+                bytes.extend_from_slice(&admin.to_le_bytes());
+            }
+            Event::Deposited { id, amount }
+            | Event::Withdrawn { id, amount }
+            | Event::MinterSet {
+                id,
+                allowance: amount,
+            }
+            | Event::MintedTo { id, amount } => {
+                bytes.extend_from_slice(&id.block.to_le_bytes());
+                bytes.extend_from_slice(&id.tx.to_le_bytes());
+                bytes.extend_from_slice(&amount.to_le_bytes());
+            }
+            Event::AirdropFunded { amount, expiry } => {
+                bytes.extend_from_slice(&amount.to_le_bytes());
+                bytes.extend_from_slice(&expiry.to_le_bytes());
+            }
+            Event::AirdropClaimed { amount } | Event::AirdropReclaimed { amount } => {
+                bytes.extend_from_slice(&amount.to_le_bytes());
+            }
+            Event::DataAppended { len } | Event::DataSealed { len } => {
+                bytes.extend_from_slice(&len.to_le_bytes());
+            }
+            Event::AttributeSet { key } => {
+                bytes.extend_from_slice(&key.to_le_bytes());
+            }
+            Event::AttributesFrozen | Event::DataReferenceSet | Event::DataUploadAborted => {}
+            Event::StateChanged { state, go_live } => {
+                bytes.push(*state);
+                bytes.extend_from_slice(&go_live.to_le_bytes());
+            }
+        }
+    }
+
+    /// Read one event from `reader`
+    fn decode_from(reader: &mut Reader) -> Result<Self> {
+        Ok(match reader.u8()? {
+            0 => Event::Initialized {
+                token_units: reader.u128()?,
+                value_per_mint: reader.u128()?,
+                cap: reader.u128()?,
+            },
+            1 => Event::Minted {
+                index: reader.u128()?,
+                amount: reader.u128()?,
+            },
+            2 => Event::Burned {
+                amount: reader.u128()?,
+            },
+            3 => Event::ParamsChanged {
+                action: reader.u128()?,
+                value: reader.u128()?,
+            },
+            4 => Event::Finalized {
+                height: reader.u64()?,
+                minted: reader.u128()?,
+            },
+            5 => Event::ChangeQueued {
+                change_id: reader.u32()?,
+                action: reader.u128()?,
+                value: reader.u128()?,
+                eta: reader.u64()?,
+            },
+            6 => Event::ChangeCancelled {
+                change_id: reader.u32()?,
+            },
+            7 => Event::Approved {
+                proposal_id: reader.u128()?,
+                admin: reader.u32()?,
+            },
+            8 => Event::Deposited {
+                id: reader.alkane_id()?,
+                amount: reader.u128()?,
+            },
+            9 => Event::Withdrawn {
+                id: reader.alkane_id()?,
+                amount: reader.u128()?,
+            },
+            10 => Event::AirdropFunded {
+                amount: reader.u128()?,
+                expiry: reader.u64()?,
+            },
+            11 => Event::AirdropClaimed {
+                amount: reader.u128()?,
+            },
+            12 => Event::AirdropReclaimed {
+                amount: reader.u128()?,
+            },
+            13 => Event::DataAppended { len: reader.u64()? },
+            14 => Event::DataSealed { len: reader.u64()? },
+            15 => Event::AttributeSet {
+                key: reader.u128()?,
+            },
+            16 => Event::AttributesFrozen,
+            17 => Event::DataReferenceSet,
+            18 => Event::StateChanged {
+                state: reader.u8()?,
+                go_live: reader.u64()?,
+            },
+            19 => Event::MinterSet {
+                id: reader.alkane_id()?,
+                allowance: reader.u128()?,
+            },
+            20 => Event::MintedTo {
+                id: reader.alkane_id()?,
+                amount: reader.u128()?,
+            },
+            21 => Event::DataUploadAborted,
+            tag => return Err(anyhow!("unknown event tag {}", tag)),
+        })
+    }
+}
+
+/// Encode events as a block, footer included
+pub fn encode_events(events: &[Event]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for event in events {
+        event.encode_into(&mut bytes);
+    }
+    let block_len = bytes.len() as u32;
+    bytes.extend_from_slice(&block_len.to_le_bytes());
+    bytes.push(EVENT_VERSION);
+    bytes.extend_from_slice(EVENT_MAGIC);
+    bytes
+}
+
+/// Split response data into the opcode's own data and its events
+///
+/// Responses without an event block are returned whole with no events.
+pub fn decode_events(data: &[u8]) -> Result<(&[u8], Vec<Event>)> {
+    if data.len() < EVENT_FOOTER_LEN || &data[data.len() - 4..] != EVENT_MAGIC {
+        return Ok((data, Vec::new()));
+    }
+    let footer = data.len() - EVENT_FOOTER_LEN;
+    let version = data[footer + 4];
+    if version != EVENT_VERSION {
+        return Err(anyhow!("unsupported event version {}", version));
+    }
+    let block_len = u32::from_le_bytes(data[footer..footer + 4].try_into()?) as usize;
+    let start = footer
+        .checked_sub(block_len)
+        .ok_or_else(|| anyhow!("event block length {} exceeds response", block_len))?;
+
+    let mut reader = Reader::new(&data[start..footer]);
+    let mut events = Vec::new();
+    while !reader.is_empty() {
+        events.push(Event::decode_from(&mut reader)?);
+    }
+    Ok((&data[..start], events))
+}
@@ -0,0 +1,180 @@
+//! Mint records and the append-only mint ledger
+//!
+//! Every successful `MintTokens` call stores a record under its txid in
+//! `/tx-hashes/`, so wallets can confirm whether a transaction minted. Every
+//! mint, the premine and delegated `MintTo` mints included, appends an entry
+//! to the `/mints` ledger keyed by mint index, so mint history and the whole
+//! supply can be rebuilt without replaying the chain.
+
+use alkanes_support::id::AlkaneId;
+use anyhow::{anyhow, Result};
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::{Script, Transaction, Txid};
+use ordinals::{Artifact, Runestone};
+use protorune_support::protostone::Protostone;
+
+/// Maximum number of ledger entries returned by one `GetMintRange` call
+pub const MAX_MINT_RANGE: u128 = 100;
+
+/// Record of one mint, stored under the minting txid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MintRecord {
+    /// Zero-based position of the mint in the mint ledger
+    pub index: u128,
+    /// Block height of the mint
+    pub height: u64,
+    /// Tokens minted
+    pub amount: u128,
+}
+
+impl MintRecord {
+    /// Encoded size: index (u128) + height (u64) + amount (u128)
+    pub const ENCODED_LEN: usize = 40;
+
+    /// Encode as little-endian fields
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_LEN);
+        bytes.extend_from_slice(&self.index.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(&self.amount.to_le_bytes());
+        bytes
+    }
+
+    /// Decode from the layout produced by `encode`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(anyhow!("invalid mint record length {}", bytes.len()));
+        }
+        Ok(Self {
+            index: u128::from_le_bytes(bytes[0..16].try_into()?),
+            height: u64::from_le_bytes(bytes[16..24].try_into()?),
+            amount: u128::from_le_bytes(bytes[24..40].try_into()?),
+        })
+    }
+}
+
+/// One entry of the mint ledger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerEntry {
+    /// Block height of the mint
+    pub height: u64,
+    /// Minting transaction
+    pub txid: Txid,
+    /// Tokens minted
+    pub amount: u128,
+    /// SHA-256 of the recipient output script, see `recipient_script_hash`,
+    /// or of the minter's AlkaneId for `MintTo`, see `minter_recipient_hash`
+    pub recipient_script_hash: [u8; 32],
+}
+
+impl LedgerEntry {
+    /// Encoded size: height (u64) + txid (32) + amount (u128) + script hash (32)
+    pub const ENCODED_LEN: usize = 88;
+
+    /// Encode as little-endian fields, txid in internal byte order
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_LEN);
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(self.txid.as_byte_array());
+        bytes.extend_from_slice(&self.amount.to_le_bytes());
+        bytes.extend_from_slice(&self.recipient_script_hash);
+        bytes
+    }
+
+    /// Decode from the layout produced by `encode`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(anyhow!("invalid ledger entry length {}", bytes.len()));
+        }
+        Ok(Self {
+            height: u64::from_le_bytes(bytes[0..8].try_into()?),
+            txid: Txid::from_slice(&bytes[8..40])?,
+            amount: u128::from_le_bytes(bytes[40..56].try_into()?),
+            recipient_script_hash: bytes[56..88].try_into()?,
+        })
+    }
+
+    /// Decode a `GetMintRange` response into its entries
+    pub fn decode_range(bytes: &[u8]) -> Result<Vec<Self>> {
+        if bytes.len() % Self::ENCODED_LEN != 0 {
+            return Err(anyhow!("invalid mint range length {}", bytes.len()));
+        }
+        bytes
+            .chunks(Self::ENCODED_LEN)
+            .map(Self::decode)
+            .collect()
+    }
+}
+
+/// Pointer of the protostone executing at virtual output `vout`
+///
+/// Protostones are numbered after the real outputs and the runestone's own
+/// slot, so the first one runs at `tx.output.len() + 1`.
+fn protostone_pointer(tx: &Transaction, vout: u32) -> Option<u32> {
+    let index = vout.checked_sub(tx.output.len() as u32 + 1)? as usize;
+    let runestone = match Runestone::decipher(tx)? {
+        Artifact::Runestone(runestone) => runestone,
+        _ => return None,
+    };
+    Protostone::from_runestone(&runestone)
+        .ok()?
+        .get(index)?
+        .pointer
+}
+
+/// Script of the output receiving the tokens returned by the call running
+/// at virtual output `vout`
+///
+/// That is the output named by the protostone's pointer, or the first
+/// non-OP_RETURN output when it has none. Pointers to another protostone or
+/// to an OP_RETURN output have no recipient script.
+pub fn recipient_script(tx: &Transaction, vout: u32) -> Option<&Script> {
+    let output = match protostone_pointer(tx, vout) {
+        Some(pointer) => tx.output.get(pointer as usize)?,
+        None => tx
+            .output
+            .iter()
+            .find(|output| !output.script_pubkey.is_op_return())?,
+    };
+    if output.script_pubkey.is_op_return() {
+        return None;
+    }
+    Some(output.script_pubkey.as_script())
+}
+
+/// SHA-256 of the recipient script, see `recipient_script`; all zeros if
+/// the transaction has no such output
+pub fn recipient_script_hash(tx: &Transaction, vout: u32) -> [u8; 32] {
+    recipient_script(tx, vout)
+        .map(|script| sha256::Hash::hash(script.as_bytes()).to_byte_array())
+        .unwrap_or([0u8; 32])
+}
+
+/// Recipient hash of a delegated mint: SHA-256 of the minter's AlkaneId
+/// (block then tx, each a u128 LE), since the tokens go to the calling
+/// contract rather than to an output
+pub fn minter_recipient_hash(id: &AlkaneId) -> [u8; 32] {
+    let bytes: Vec<u8> = id.clone().into();
+    sha256::Hash::hash(&bytes).to_byte_array()
+}
+
+/// Build a txid from its opcode input form
+///
+/// The txid bytes are taken in internal (little-endian) order: `txid_lo`
+/// holds bytes 0..16 and `txid_hi` bytes 16..32, each as a little-endian u128.
+pub fn txid_from_parts(txid_hi: u128, txid_lo: u128) -> Txid {
+    let mut bytes = [0u8; 32];
+    bytes[0..16].copy_from_slice(&txid_lo.to_le_bytes());
+    bytes[16..32].copy_from_slice(&txid_hi.to_le_bytes());
+    Txid::from_byte_array(bytes)
+}
+
+/// Split a txid into its opcode input form `(txid_hi, txid_lo)`
+pub fn txid_to_parts(txid: &Txid) -> (u128, u128) {
+    let bytes = txid.as_byte_array();
+    let mut lo = [0u8; 16];
+    let mut hi = [0u8; 16];
+    lo.copy_from_slice(&bytes[0..16]);
+    hi.copy_from_slice(&bytes[16..32]);
+    (u128::from_le_bytes(hi), u128::from_le_bytes(lo))
+}
@@ -9,25 +9,158 @@ use alkanes_runtime::{declare_alkane, message::MessageDispatch, runtime::AlkaneR
 use alkanes_support::gz;
 use alkanes_support::response::CallResponse;
 use alkanes_support::utils::overflow_error;
-use alkanes_support::witness::find_witness_payload;
 use alkanes_support::{context::Context, parcel::AlkaneTransfer, parcel::AlkaneTransferParcel};
 use alkanes_support::id::AlkaneId;
 use alkanes_support::cellpack::Cellpack;
 use anyhow::{anyhow, Result};
-use bitcoin::hashes::Hash;
+use bitcoin::hashes::{sha256, Hash};
 use bitcoin::{Transaction, Txid};
 use metashrew_support::compat::to_arraybuffer_layout;
 use metashrew_support::index_pointer::KeyValuePointer;
 use metashrew_support::utils::consensus_decode;
 use std::io::Cursor;
 use std::sync::Arc;
+pub mod admin;
+pub mod airdrop;
+pub mod checkpoints;
+mod codec;
+pub mod config;
+pub mod data;
+pub mod events;
+pub mod ledger;
+pub mod lifecycle;
+pub mod metadata;
+pub mod minters;
+pub mod packing;
 #[cfg(test)]
 pub mod tests;
+pub mod timelock;
+pub mod token_info;
+pub mod treasury;
+
+use admin::{MultisigAdmin, MAX_ADMINS};
+use airdrop::{root_from_parts, Airdrop};
+use checkpoints::CheckpointList;
+use config::{config_payload, InitConfig};
+use data::{
+    content_type_from_words, envelope_payload, is_gzip, sniff_content_type, DataCommitment,
+    DataInfo, DataReference, DataUpload, ENCODING_GZIP, ENCODING_IDENTITY, MAX_DATA_RANGE,
+};
+use events::{encode_events, Event};
+use ledger::{
+    minter_recipient_hash, recipient_script, recipient_script_hash, txid_from_parts, LedgerEntry,
+    MintRecord, MAX_MINT_RANGE,
+};
+use lifecycle::{LaunchState, Lifecycle, ANY_STATE, OPEN_STATES, VIEW_STATES};
+use metadata::{encode_keys, Metadata};
+use minters::Minters;
+use timelock::{
+    ChangeStatus, QueuedChange, Timelock, ACTION_SET_CAP, ACTION_SET_TIMELOCK_DELAY,
+    ACTION_SET_VALUE_PER_MINT,
+};
+use token_info::TokenInfo;
+use treasury::Treasury;
 
 /// Constants for token identification
 pub const ALKANE_FACTORY_OWNED_TOKEN_ID: u128 = 0x0fff;
 pub const ALKANE_FACTORY_FREE_MINT_ID: u128 = 0x0ffe;
 
+/// Optional Initialize inputs that follow the six fixed fields, as indices
+/// into the context inputs (index 0 is the opcode). Passing 0, or leaving an
+/// input out, selects the default.
+///
+/// Number of admin tokens to issue (default 1)
+pub const INIT_INPUT_ADMIN_COUNT: usize = 7;
+/// Number of admin tokens required to authorize an admin call (default 1)
+pub const INIT_INPUT_ADMIN_THRESHOLD: usize = 8;
+/// Timelock delay in blocks for queued admin changes (default 0)
+pub const INIT_INPUT_TIMELOCK_DELAY: usize = 9;
+/// Display decimals (default `DEFAULT_DECIMALS` when the inputs stop before
+/// it); unlike the other options an explicit 0 is kept as 0
+pub const INIT_INPUT_DECIMALS: usize = 10;
+/// MIME type of the data segment of up to 16 bytes, packed little-endian
+/// (default: sniffed from the data; longer types use the word list option)
+pub const INIT_INPUT_CONTENT_TYPE: usize = 11;
+/// Total size of a data segment uploaded over several transactions with
+/// `AppendData` (default: the Initialize witness holds the whole segment)
+pub const INIT_INPUT_DATA_SIZE: usize = 12;
+/// High half (bytes 16..32) of the SHA-256 the uploaded segment must match
+pub const INIT_INPUT_DATA_HASH_HI: usize = 13;
+/// Low half (bytes 0..16) of the SHA-256 the uploaded segment must match
+pub const INIT_INPUT_DATA_HASH_LO: usize = 14;
+/// Number of words in a long name, followed by the words themselves and then
+/// the number of words in a long symbol and its words (default 0: use the
+/// fixed name and symbol inputs)
+pub const INIT_INPUT_NAME_WORDS: usize = 15;
+
+/// Options after the symbol words, as offsets from the input following them
+///
+/// Transaction input holding the data envelope, plus one (default: the first
+/// envelope found in any input)
+pub const INIT_OPTION_DATA_INPUT: usize = 0;
+/// Largest envelope accepted as token data, in bytes (default: no limit)
+pub const INIT_OPTION_MAX_DATA_LEN: usize = 1;
+/// Number of words in a long MIME type, followed by the words themselves
+/// (default 0: use the fixed content type input)
+pub const INIT_OPTION_CONTENT_TYPE_WORDS: usize = 2;
+/// Start in Draft when non-zero (default: Live); read from the input right
+/// after the content type words
+pub const INIT_OPTION_DRAFT: usize = 0;
+
+/// Most u128 words a long name or symbol may span (256 bytes)
+pub const MAX_STRING_WORDS: u128 = 16;
+
+/// Decimals reported when none were given at initialization
+pub const DEFAULT_DECIMALS: u8 = 8;
+/// Largest decimals value that still fits a u128 amount
+pub const MAX_DECIMALS: u8 = 38;
+
+/// Read an optional trailing input, treating 0 as not given
+fn optional_input(context: &Context, index: usize) -> Option<u128> {
+    context.inputs.get(index).cloned().filter(|v| *v != 0)
+}
+
+/// Read a word count at `index` and the words after it, returning the words
+/// and the index following them
+fn string_words(context: &Context, index: usize) -> Result<(Vec<u128>, usize)> {
+    let count = context.inputs.get(index).cloned().unwrap_or(0);
+    if count > MAX_STRING_WORDS {
+        return Err(anyhow!(
+            "strings are limited to {} words, got {}",
+            MAX_STRING_WORDS,
+            count
+        ));
+    }
+    let start = index + 1;
+    let end = start + count as usize;
+    let words = context
+        .inputs
+        .get(start..end)
+        .ok_or_else(|| anyhow!("expected {} string words after input {}", count, index))?;
+    Ok((words.to_vec(), end))
+}
+
+/// Append an event block to the response data
+fn emit_events(response: &mut CallResponse, events: &[Event]) {
+    response.data.extend(encode_events(events));
+}
+
+/// Converts a queued change id from opcode input form
+fn change_id_to_u32(change_id: u128) -> Result<u32> {
+    u32::try_from(change_id).map_err(|_| anyhow!("unknown queued change {}", change_id))
+}
+
+/// Decodes an AlkaneId from its 32-byte little-endian storage form
+pub fn alkane_id_from_bytes(bytes: &[u8]) -> Result<AlkaneId> {
+    if bytes.len() != 32 {
+        return Err(anyhow!("invalid alkane id length {}", bytes.len()));
+    }
+    Ok(AlkaneId::new(
+        u128::from_le_bytes(bytes[0..16].try_into()?),
+        u128::from_le_bytes(bytes[16..32].try_into()?),
+    ))
+}
+
 /// Returns a StoragePointer for the token name
 fn name_pointer() -> StoragePointer {
     StoragePointer::from_keyword("/name")
@@ -38,19 +171,12 @@ fn symbol_pointer() -> StoragePointer {
     StoragePointer::from_keyword("/symbol")
 }
 
-/// Trims a u128 value to a String by removing trailing zeros
+/// Trims a u128 value to a String at its first zero byte
+///
+/// Invalid UTF-8 is replaced rather than rejected; inputs that must be
+/// validated go through `packing::decode_word` instead.
 pub fn trim(v: u128) -> String {
-    String::from_utf8(
-        v.to_le_bytes()
-            .into_iter()
-            .fold(Vec::<u8>::new(), |mut r, v| {
-                if v != 0 {
-                    r.push(v)
-                }
-                r
-            }),
-    )
-    .unwrap()
+    String::from_utf8_lossy(&packing::decode_bytes(&[v])).into_owned()
 }
 
 /// TokenName struct to hold two u128 values for the name
@@ -71,6 +197,16 @@ impl TokenName {
     pub fn new(part1: u128, part2: u128) -> Self {
         Self { part1, part2 }
     }
+
+    /// Decode each part up to its own terminator and join them, failing on
+    /// invalid UTF-8
+    pub fn decode(&self) -> Result<String> {
+        Ok(format!(
+            "{}{}",
+            packing::decode_word(self.part1)?,
+            packing::decode_word(self.part2)?
+        ))
+    }
 }
 
 pub struct ContextHandle(());
@@ -285,6 +421,12 @@ pub trait MintableToken: AlkaneResponder {
         self.set_string_field(self.symbol_pointer(), symbol);
     }
 
+    /// Set a token name and symbol of any length
+    fn set_long_name_and_symbol(&self, name: &str, symbol: &str) {
+        self.name_pointer().set(Arc::new(name.as_bytes().to_vec()));
+        self.symbol_pointer().set(Arc::new(symbol.as_bytes().to_vec()));
+    }
+
     /// Get the pointer to the token name
     fn name_pointer(&self) -> StoragePointer {
         name_pointer()
@@ -295,6 +437,30 @@ pub trait MintableToken: AlkaneResponder {
         symbol_pointer()
     }
 
+    /// Get the pointer to the token decimals
+    fn decimals_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/decimals")
+    }
+
+    /// Get the token decimals
+    fn decimals(&self) -> u8 {
+        let pointer = self.decimals_pointer();
+        if pointer.get().len() == 0 {
+            DEFAULT_DECIMALS
+        } else {
+            pointer.get_value::<u8>()
+        }
+    }
+
+    /// Set the token decimals
+    fn set_decimals(&self, v: u8) -> Result<()> {
+        if v > MAX_DECIMALS {
+            return Err(anyhow!("decimals {} exceeds {}", v, MAX_DECIMALS));
+        }
+        self.decimals_pointer().set_value::<u8>(v);
+        Ok(())
+    }
+
     /// Set a string field in storage
     fn set_string_field(&self, mut pointer: StoragePointer, v: u128) {
         pointer.set(Arc::new(trim(v).as_bytes().to_vec()));
@@ -324,6 +490,16 @@ pub trait MintableToken: AlkaneResponder {
         Ok(())
     }
 
+    /// Decrease the total supply
+    fn decrease_total_supply(&self, v: u128) -> Result<()> {
+        self.set_total_supply(
+            self.total_supply()
+                .checked_sub(v)
+                .ok_or_else(|| anyhow!("total supply underflow"))?,
+        );
+        Ok(())
+    }
+
     /// Mint new tokens
     fn mint(&self, context: &Context, value: u128) -> Result<AlkaneTransfer> {
         self.increase_total_supply(value)?;
@@ -338,20 +514,356 @@ pub trait MintableToken: AlkaneResponder {
         StoragePointer::from_keyword("/data")
     }
 
-    /// Get the token data
+    /// Get the pointer to the flag recording whether the token data is gzip-compressed
+    fn data_compressed_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-compressed")
+    }
+
+    /// Check whether the token data is stored gzip-compressed
+    ///
+    /// Deployments that predate the flag are checked for the gzip magic bytes.
+    fn data_is_compressed(&self) -> bool {
+        let flag = self.data_compressed_pointer().get();
+        if flag.len() == 0 {
+            return is_gzip(&self.data_pointer().get());
+        }
+        flag[0] != 0
+    }
+
+    /// Get the token data as stored, compressed or not
+    fn raw_data(&self) -> Vec<u8> {
+        self.data_pointer().get().as_ref().clone()
+    }
+
+    /// Get the pointer to the decompressed copy of gzip-compressed token data
+    fn data_plain_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-plain")
+    }
+
+    /// Get the decompressed token data, from the cache when there is one
+    fn plain_data(&self) -> Arc<Vec<u8>> {
+        if !self.data_is_compressed() {
+            return self.data_pointer().get();
+        }
+        let cached = self.data_plain_pointer().get();
+        if cached.len() != 0 {
+            return cached;
+        }
+        Arc::new(gz::decompress(self.raw_data()).unwrap_or_else(|_| vec![]))
+    }
+
+    /// Get the token data, decompressed when stored as gzip
     fn data(&self) -> Vec<u8> {
-        gz::decompress(self.data_pointer().get().as_ref().clone()).unwrap_or_else(|_| vec![])
+        self.plain_data().as_ref().clone()
+    }
+
+    /// Get the length of the decompressed token data
+    fn data_len(&self) -> u128 {
+        self.plain_data().len() as u128
+    }
+
+    /// Get up to `length` bytes of the decompressed token data starting at `offset`
+    fn data_range(&self, offset: u128, length: u128) -> Vec<u8> {
+        let data = self.plain_data();
+        let end = offset
+            .saturating_add(length.min(MAX_DATA_RANGE))
+            .min(data.len() as u128);
+        data[offset.min(end) as usize..end as usize].to_vec()
+    }
+
+    /// Store the token data along with its compression flag, caching the
+    /// decompressed form of gzip data so reads don't decompress every time
+    ///
+    /// Data is only treated as gzip when it also decompresses; raw data that
+    /// happens to start with the magic bytes is stored and served as is.
+    fn store_data(&self, data: Vec<u8>) -> Result<()> {
+        let plain = if is_gzip(&data) {
+            gz::decompress(data.clone()).ok()
+        } else {
+            None
+        };
+        let compressed = plain.is_some();
+        self.data_plain_pointer()
+            .set(Arc::new(plain.unwrap_or_default()));
+        self.data_compressed_pointer()
+            .set(Arc::new(vec![compressed as u8]));
+        self.data_pointer().set(Arc::new(data));
+        Ok(())
+    }
+
+    /// Get the pointer to the input holding data envelopes, plus one (0 to scan all inputs)
+    fn data_input_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-input")
+    }
+
+    /// Get the input holding data envelopes, or None to scan all inputs
+    fn data_input(&self) -> Option<u32> {
+        self.data_input_pointer().get_value::<u32>().checked_sub(1)
+    }
+
+    /// Get the pointer to the largest envelope accepted as data (0 for no limit)
+    fn data_max_len_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-max-len")
     }
 
-    /// Set the token data from the transaction
+    /// Get the largest envelope accepted as data, 0 for no limit
+    fn data_max_len(&self) -> u64 {
+        self.data_max_len_pointer().get_value::<u64>()
+    }
+
+    /// Set where data envelopes are read from and how large they may be
+    fn set_data_envelope_options(&self, input: Option<u32>, max_len: u64) -> Result<()> {
+        // Stored plus one so that 0 can mean every input
+        let stored = match input {
+            Some(input) => input
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("data input {} out of range", input))?,
+            None => 0,
+        };
+        self.data_input_pointer().set_value::<u32>(stored);
+        self.data_max_len_pointer().set_value::<u64>(max_len);
+        Ok(())
+    }
+
+    /// Refuse data larger than the configured limit
+    fn check_data_len(&self, len: u64) -> Result<()> {
+        let max_len = self.data_max_len();
+        if max_len != 0 && len > max_len {
+            return Err(anyhow!(
+                "data of {} bytes exceeds the {}-byte limit",
+                len,
+                max_len
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the config record of the current transaction, empty when it has none
+    fn init_config(&self) -> Result<InitConfig> {
+        let tx = consensus_decode::<Transaction>(&mut Cursor::new(CONTEXT.transaction()))?;
+        match config_payload(&tx) {
+            Some(payload) => InitConfig::decode(&payload),
+            None => Ok(InitConfig::default()),
+        }
+    }
+
+    /// Get the data envelope payload of `tx`, applying the envelope options
+    fn envelope_data(&self, tx: &Transaction) -> Result<Vec<u8>> {
+        let payload = envelope_payload(tx, self.data_input()).unwrap_or_default();
+        self.check_data_len(payload.len() as u64)?;
+        Ok(payload)
+    }
+
+    /// Get the data envelope payload of the current transaction
+    fn witness_data(&self) -> Result<Vec<u8>> {
+        let tx = consensus_decode::<Transaction>(&mut Cursor::new(CONTEXT.transaction()))?;
+        self.envelope_data(&tx)
+    }
+
+    /// Set the token data from the transaction and commit its hash
     fn set_data(&self) -> Result<()> {
         let tx = consensus_decode::<Transaction>(&mut Cursor::new(CONTEXT.transaction()))?;
-        let data: Vec<u8> = find_witness_payload(&tx, 0).unwrap_or_else(|| vec![]);
+        self.store_data(self.envelope_data(&tx)?)?;
+        self.commit_data_hash(&tx.compute_txid(), self.height());
+        Ok(())
+    }
+
+    /// Get the pointer to the data hash history
+    fn data_hashes_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-hashes")
+    }
+
+    /// Get the latest data hash commitment, if any was recorded
+    fn data_commitment(&self) -> Option<DataCommitment> {
+        let pointer = self.data_hashes_pointer();
+        match pointer.length() {
+            0 => None,
+            len => DataCommitment::decode(&pointer.select_index(len - 1).get()).ok(),
+        }
+    }
+
+    /// Get every data hash commitment, oldest first, encoded back to back
+    fn data_hash_history(&self) -> Vec<u8> {
+        let pointer = self.data_hashes_pointer();
+        (0..pointer.length())
+            .flat_map(|i| pointer.select_index(i).get().as_ref().clone())
+            .collect()
+    }
+
+    /// Record the hash of the stored data segment and the txid it came from
+    fn commit_data_hash(&self, txid: &Txid, height: u64) -> DataCommitment {
+        let commitment = DataCommitment {
+            sha256: sha256::Hash::hash(&self.raw_data()).to_byte_array(),
+            txid: *txid,
+            height,
+        };
+        self.data_hashes_pointer()
+            .append(Arc::new(commitment.encode()));
+        commitment
+    }
+
+    /// Get the pointer to the pending data upload, empty once sealed
+    fn data_upload_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-upload")
+    }
+
+    /// Get the pending data upload, if the segment is not sealed yet
+    fn data_upload(&self) -> Result<Option<DataUpload>> {
+        let bytes = self.data_upload_pointer().get();
+        if bytes.len() == 0 {
+            return Ok(None);
+        }
+        DataUpload::decode(&bytes).map(Some)
+    }
+
+    /// Refuse to serve a data segment that is still being uploaded
+    fn require_data_sealed(&self) -> Result<()> {
+        if self.data_upload_pointer().get().len() != 0 {
+            return Err(anyhow!("data segment is not sealed"));
+        }
+        Ok(())
+    }
+
+    /// Get the pointer to the external data reference
+    fn data_reference_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-ref")
+    }
+
+    /// Get the external data reference, if the data lives elsewhere
+    fn data_reference(&self) -> Result<Option<DataReference>> {
+        let bytes = self.data_reference_pointer().get();
+        if bytes.len() == 0 {
+            return Ok(None);
+        }
+        DataReference::decode(&bytes).map(Some)
+    }
+
+    /// Point the token data at an inscription or URI instead of embedding it
+    fn set_data_reference(&self, reference: &DataReference) -> Result<()> {
+        if self.data_reference()?.is_some() {
+            return Err(anyhow!("data reference already set"));
+        }
+        if self.data_upload()?.is_some() || !self.raw_data().is_empty() {
+            return Err(anyhow!("token data is already embedded"));
+        }
+        self.data_reference_pointer()
+            .set(Arc::new(reference.encode()));
+        Ok(())
+    }
+
+    /// Refuse to serve data that is still being uploaded or lives elsewhere
+    fn require_data_available(&self) -> Result<()> {
+        self.require_data_sealed()?;
+        if let Some(reference) = self.data_reference()? {
+            return Err(anyhow!(
+                "token data is not stored in the contract, it lives at {}",
+                reference
+            ));
+        }
+        Ok(())
+    }
+
+    /// Start a data upload with its first chunk
+    fn begin_data_upload(&self, upload: DataUpload, chunk: Vec<u8>) -> Result<()> {
+        if chunk.len() as u64 > upload.size {
+            return Err(anyhow!(
+                "data upload exceeds declared size: {} > {}",
+                chunk.len(),
+                upload.size
+            ));
+        }
+        self.data_pointer().set(Arc::new(chunk));
+        self.data_upload_pointer().set(Arc::new(upload.encode()));
+        Ok(())
+    }
+
+    /// Append a chunk to the pending upload, returning the size so far
+    fn append_data_chunk(&self, chunk: &[u8]) -> Result<u64> {
+        let upload = self
+            .data_upload()?
+            .ok_or_else(|| anyhow!("data segment is sealed"))?;
+        if chunk.is_empty() {
+            return Err(anyhow!("data chunk is empty"));
+        }
+        let mut data = self.raw_data();
+        let len = (data.len() + chunk.len()) as u64;
+        if len > upload.size {
+            return Err(anyhow!(
+                "data upload exceeds declared size: {} > {}",
+                len,
+                upload.size
+            ));
+        }
+        data.extend_from_slice(chunk);
         self.data_pointer().set(Arc::new(data));
+        Ok(len)
+    }
 
+    /// Drop the chunks of the pending upload so it can be uploaded again,
+    /// keeping its declared size, hash and content type
+    fn discard_data_chunks(&self) -> Result<()> {
+        if self.data_upload()?.is_none() {
+            return Err(anyhow!("data segment is sealed"));
+        }
+        self.data_pointer().set(Arc::new(Vec::new()));
         Ok(())
     }
 
+    /// Check the complete upload against its declared size and hash, lock it
+    /// and commit its hash with the sealing transaction
+    fn seal_data_upload(&self, txid: &Txid, height: u64) -> Result<u64> {
+        let upload = self
+            .data_upload()?
+            .ok_or_else(|| anyhow!("data segment is sealed"))?;
+        let data = self.raw_data();
+        if data.len() as u64 != upload.size {
+            return Err(anyhow!(
+                "data upload incomplete: {} of {} bytes",
+                data.len(),
+                upload.size
+            ));
+        }
+        if sha256::Hash::hash(&data).to_byte_array() != upload.sha256 {
+            return Err(anyhow!("data upload hash mismatch"));
+        }
+        self.data_upload_pointer().set(Arc::new(Vec::new()));
+        self.store_data(data)?;
+        self.commit_data_hash(txid, height);
+        self.record_data_info(Some(upload.content_type).filter(|c| !c.is_empty()))?;
+        Ok(upload.size)
+    }
+
+    /// Get the pointer to the data segment description
+    fn data_info_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/data-info")
+    }
+
+    /// Get the data segment description
+    fn data_info(&self) -> Result<DataInfo> {
+        DataInfo::decode(&self.data_info_pointer().get())
+    }
+
+    /// Describe the stored data segment, sniffing the content type when none is given
+    fn record_data_info(&self, content_type: Option<String>) -> Result<DataInfo> {
+        let stored = self.raw_data();
+        let plain = self.data();
+        let encoding = if self.data_is_compressed() {
+            ENCODING_GZIP
+        } else {
+            ENCODING_IDENTITY
+        };
+        let info = DataInfo {
+            content_type: content_type
+                .unwrap_or_else(|| sniff_content_type(&plain).to_string()),
+            encoding: encoding.to_string(),
+            stored_len: stored.len() as u64,
+            plain_len: plain.len() as u64,
+            sha256: sha256::Hash::hash(&stored).to_byte_array(),
+        };
+        self.data_info_pointer().set(Arc::new(info.encode()));
+        Ok(info)
+    }
+
     /// Observe initialization to prevent multiple initializations
     fn observe_initialization(&self) -> Result<()> {
         let mut pointer = StoragePointer::from_keyword("/initialized");
@@ -370,6 +882,20 @@ pub struct MintableAlkane(());
 
 impl MintableToken for MintableAlkane {}
 
+impl MultisigAdmin for MintableAlkane {}
+
+impl Timelock for MintableAlkane {}
+
+impl Treasury for MintableAlkane {}
+
+impl Airdrop for MintableAlkane {}
+
+impl Metadata for MintableAlkane {}
+
+impl Lifecycle for MintableAlkane {}
+
+impl Minters for MintableAlkane {}
+
 /// Message enum for opcode-based dispatch
 #[derive(MessageDispatch)]
 enum MintableAlkaneMessage {
@@ -390,10 +916,155 @@ enum MintableAlkaneMessage {
         symbol: u128,
     },
 
+    /// Drop the chunks of a pending data upload so it can start over (owner only)
+    #[opcode(75)]
+    AbortDataUpload,
+
+    /// Mint a custom amount to the calling contract (approved minters only)
+    #[opcode(76)]
+    MintTo {
+        /// Amount to mint, charged to the caller's allowance
+        amount: u128,
+    },
+
     /// Mint new tokens
     #[opcode(77)]
     MintTokens,
 
+    /// Close minting early and freeze the supply (owner only)
+    #[opcode(78)]
+    Finalize,
+
+    /// Approve an admin call identified by its proposal id (admin token holders)
+    #[opcode(79)]
+    Approve {
+        /// Proposal id, see `admin::proposal_id`
+        proposal_id: u128,
+    },
+
+    /// Queue a timelocked parameter change, applied at once in Draft (owner only)
+    #[opcode(80)]
+    QueueChange {
+        /// One of the `timelock::ACTION_*` constants
+        action: u128,
+        /// New value for the parameter
+        value: u128,
+    },
+
+    /// Execute a queued change once its delay has passed (anyone)
+    #[opcode(81)]
+    ExecuteChange {
+        /// Id returned when the change was queued
+        change_id: u128,
+    },
+
+    /// Cancel a pending queued change (owner only)
+    #[opcode(82)]
+    CancelChange {
+        /// Id returned when the change was queued
+        change_id: u128,
+    },
+
+    /// Keep the incoming alkanes in the treasury (admin tokens are returned)
+    #[opcode(83)]
+    Deposit,
+
+    /// Withdraw alkanes held by the treasury (owner only)
+    #[opcode(84)]
+    Withdraw {
+        /// AlkaneId block of the alkane to withdraw
+        block: u128,
+        /// AlkaneId tx of the alkane to withdraw
+        tx: u128,
+        /// Amount to withdraw
+        amount: u128,
+    },
+
+    /// Burn this token sent along, refunding any other alkanes
+    #[opcode(85)]
+    Burn,
+
+    /// Set an airdrop merkle root, funded with this token sent along (owner only)
+    #[opcode(86)]
+    SetAirdrop {
+        /// Root bytes 16..32, see `airdrop::root_from_parts`
+        root_hi: u128,
+        /// Root bytes 0..16
+        root_lo: u128,
+        /// Last height at which claims are accepted
+        expiry: u128,
+    },
+
+    /// Claim an airdrop leaf; the proof is read from the witness envelope.
+    /// Only accepted from the transaction itself, not from another contract
+    #[opcode(87)]
+    ClaimAirdrop {
+        /// Amount committed in the leaf
+        amount: u128,
+    },
+
+    /// Reclaim unclaimed airdrop funds after expiry (owner only)
+    #[opcode(89)]
+    ReclaimAirdrop,
+
+    /// Append the transaction's witness payload to a pending data upload (owner only)
+    #[opcode(90)]
+    AppendData,
+
+    /// Verify the uploaded data segment against its declared size and hash and lock it (owner only)
+    #[opcode(91)]
+    SealData,
+
+    /// Set a metadata attribute; a zero value takes the value from the witness (owner only)
+    #[opcode(92)]
+    SetAttribute {
+        /// Attribute key, packed into one word (see `packing`)
+        key: u128,
+        /// Short value packed into one word, or 0 to read it from the witness
+        value: u128,
+    },
+
+    /// Freeze the metadata attributes for good (owner only)
+    #[opcode(93)]
+    FreezeAttributes,
+
+    /// Point the token data at an existing inscription instead of embedding it (owner only)
+    #[opcode(94)]
+    SetInscriptionReference {
+        /// Inscription txid bytes 16..32
+        txid_hi: u128,
+        /// Inscription txid bytes 0..16
+        txid_lo: u128,
+        /// Inscription index within the transaction
+        index: u128,
+    },
+
+    /// Point the token data at the URI in the witness payload instead of embedding it (owner only)
+    #[opcode(95)]
+    SetUriReference,
+
+    /// Fix the go-live height of a Draft token (owner only)
+    #[opcode(96)]
+    ScheduleLaunch {
+        /// Height from which the token is Live
+        height: u128,
+    },
+
+    /// Close the token for good (owner only)
+    #[opcode(97)]
+    Close,
+
+    /// Set the lifetime allowance of a minter contract, 0 to revoke it (owner only)
+    #[opcode(98)]
+    SetMinter {
+        /// AlkaneId block of the minter
+        block: u128,
+        /// AlkaneId tx of the minter
+        tx: u128,
+        /// Total amount the minter may mint
+        allowance: u128,
+    },
+
     /// Get the token name
     #[opcode(99)]
     #[returns(String)]
@@ -424,10 +1095,185 @@ enum MintableAlkaneMessage {
     #[returns(u128)]
     GetValuePerMint,
 
+    /// Get the finalization record (empty if not finalized)
+    #[opcode(105)]
+    #[returns(Vec<u8>)]
+    GetFinalization,
+
+    /// Get a proposal's approval count (u128) and approving admin indices (u32 each)
+    #[opcode(106)]
+    #[returns(Vec<u8>)]
+    GetProposal {
+        /// Proposal id, see `admin::proposal_id`
+        proposal_id: u128,
+    },
+
+    /// Get the admin threshold (u128) followed by the admin token ids
+    #[opcode(107)]
+    #[returns(Vec<u8>)]
+    GetAdmins,
+
+    /// Get the timelock delay in blocks
+    #[opcode(108)]
+    #[returns(u64)]
+    GetTimelockDelay,
+
+    /// Get the number of changes ever queued
+    #[opcode(109)]
+    #[returns(u128)]
+    GetQueuedChangeCount,
+
+    /// Get an encoded queued change, see `timelock::QueuedChange`
+    #[opcode(110)]
+    #[returns(Vec<u8>)]
+    GetQueuedChange {
+        /// Id returned when the change was queued
+        change_id: u128,
+    },
+
+    /// Get the ids (u32 each) of all pending changes
+    #[opcode(111)]
+    #[returns(Vec<u8>)]
+    GetPendingChanges,
+
+    /// Get the treasury balances as (AlkaneId, recorded u128, held u128) entries
+    #[opcode(112)]
+    #[returns(Vec<u8>)]
+    GetTreasury,
+
+    /// Get the running total of burned tokens
+    #[opcode(113)]
+    #[returns(u128)]
+    GetBurned,
+
+    /// Get the token decimals
+    #[opcode(114)]
+    #[returns(u8)]
+    GetDecimals,
+
+    /// Get all token state in one versioned binary layout, see `token_info::TokenInfo`
+    #[opcode(115)]
+    #[returns(Vec<u8>)]
+    GetTokenInfo,
+
+    /// Get all token state as a JSON object
+    #[opcode(116)]
+    #[returns(String)]
+    GetTokenInfoJson,
+
+    /// Get whether a txid minted: a flag byte, then the encoded `ledger::MintRecord` if set
+    #[opcode(117)]
+    #[returns(Vec<u8>)]
+    GetMintByTxid {
+        /// Txid bytes 16..32 in internal order, see `ledger::txid_from_parts`
+        txid_hi: u128,
+        /// Txid bytes 0..16 in internal order
+        txid_lo: u128,
+    },
+
+    /// Get encoded mint ledger entries, see `ledger::LedgerEntry::decode_range`
+    #[opcode(118)]
+    #[returns(Vec<u8>)]
+    GetMintRange {
+        /// Mint index of the first entry
+        start: u128,
+        /// Number of entries (at most `ledger::MAX_MINT_RANGE`)
+        count: u128,
+    },
+
+    /// Get the number of mint ledger entries
+    #[opcode(119)]
+    #[returns(u128)]
+    GetMintCount,
+
+    /// Get the total supply as of a block height
+    #[opcode(120)]
+    #[returns(u128)]
+    GetTotalSupplyAt {
+        /// Block height
+        height: u128,
+    },
+
+    /// Get the mint counter as of a block height
+    #[opcode(121)]
+    #[returns(u128)]
+    GetMintedAt {
+        /// Block height
+        height: u128,
+    },
+
+    /// Get the airdrop root (32 bytes), expiry (u64) and unclaimed pool (u128)
+    #[opcode(122)]
+    #[returns(Vec<u8>)]
+    GetAirdrop,
+
+    /// Get a metadata attribute value, empty when unset
+    #[opcode(123)]
+    #[returns(Vec<u8>)]
+    GetAttribute {
+        /// Attribute key, packed into one word (see `packing`)
+        key: u128,
+    },
+
+    /// Get the metadata attribute keys, see `metadata::decode_keys`
+    #[opcode(124)]
+    #[returns(Vec<u8>)]
+    ListAttributeKeys,
+
+    /// Get the launch state (u8: 0 Draft, 1 Scheduled, 2 Live, 3 Closed) and go-live height (u64)
+    #[opcode(125)]
+    #[returns(Vec<u8>)]
+    GetState,
+
+    /// Get the minters as (AlkaneId, allowance u128, used u128) entries
+    #[opcode(126)]
+    #[returns(Vec<u8>)]
+    GetMinters,
+
     /// Get the token data
     #[opcode(1000)]
     #[returns(Vec<u8>)]
     GetData,
+
+    /// Get the data segment MIME type, encoding, sizes and hash, see `data::DataInfo`
+    #[opcode(1001)]
+    #[returns(Vec<u8>)]
+    GetDataInfo,
+
+    /// Get the token data exactly as stored, without decompressing it
+    #[opcode(1002)]
+    #[returns(Vec<u8>)]
+    GetRawData,
+
+    /// Get a slice of the decompressed token data
+    #[opcode(1003)]
+    #[returns(Vec<u8>)]
+    GetDataRange {
+        /// Byte offset into the decompressed data
+        offset: u128,
+        /// Number of bytes (at most `data::MAX_DATA_RANGE`)
+        length: u128,
+    },
+
+    /// Get the length of the decompressed token data
+    #[opcode(1004)]
+    #[returns(u128)]
+    GetDataLength,
+
+    /// Get the latest data hash commitment (hash, source txid, height), see `data::DataCommitment`
+    #[opcode(1005)]
+    #[returns(Vec<u8>)]
+    GetDataHash,
+
+    /// Get every data hash commitment, oldest first
+    #[opcode(1006)]
+    #[returns(Vec<u8>)]
+    GetDataHashHistory,
+
+    /// Get the inscription or URI the token data lives at, empty when embedded
+    #[opcode(1007)]
+    #[returns(Vec<u8>)]
+    GetDataReference,
 }
 
 impl MintableAlkane {
@@ -455,6 +1301,26 @@ impl MintableAlkane {
         Ok(())
     }
 
+    /// Get the pointer to the burned total
+    pub fn burned_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/burned")
+    }
+
+    /// Get the total amount burned
+    pub fn burned(&self) -> u128 {
+        self.burned_pointer().get_value::<u128>()
+    }
+
+    /// Remove tokens from the supply and add them to the burned total
+    pub fn record_burn(&self, value: u128) -> Result<()> {
+        self.decrease_total_supply(value)?;
+        self.burned_pointer().set_value::<u128>(
+            overflow_error(self.burned().checked_add(value))
+                .map_err(|_| anyhow!("burned total overflow"))?,
+        );
+        Ok(())
+    }
+
     /// Get the pointer to the value per mint
     pub fn value_per_mint_pointer(&self) -> StoragePointer {
         StoragePointer::from_keyword("/value-per-mint")
@@ -486,89 +1352,1280 @@ impl MintableAlkane {
             .set_value::<u128>(if v == 0 { u128::MAX } else { v });
     }
 
+    /// Get the pointer to the finalization record
+    pub fn finalization_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/finalization")
+    }
+
+    /// Check if minting has been finalized
+    pub fn is_finalized(&self) -> bool {
+        self.finalization_pointer().get().len() != 0
+    }
+
+    /// Get the finalization height and txid, if minting has been finalized
+    pub fn finalization(&self) -> Option<(u64, Txid)> {
+        let record = self.finalization_pointer().get();
+        if record.len() != 40 {
+            return None;
+        }
+        let height = u64::from_le_bytes(record[0..8].try_into().ok()?);
+        let txid = Txid::from_slice(&record[8..40]).ok()?;
+        Some((height, txid))
+    }
+
+    /// Freeze the cap at the current mint count and record where it happened
+    pub fn set_finalization(&self, height: u64, txid: &Txid) {
+        // Written directly: set_cap would treat a zero mint count as unlimited
+        self.cap_pointer().set_value::<u128>(self.minted());
+        let mut record = height.to_le_bytes().to_vec();
+        record.extend_from_slice(txid.as_byte_array());
+        self.finalization_pointer().set(Arc::new(record));
+    }
+
+    /// Get the pointer to a transaction's mint entry
+    pub fn tx_hash_pointer(&self, txid: &Txid) -> StoragePointer {
+        StoragePointer::from_keyword("/tx-hashes/").select(&txid.as_byte_array().to_vec())
+    }
+
     /// Check if a transaction hash has been used for minting
     pub fn has_tx_hash(&self, txid: &Txid) -> bool {
-        StoragePointer::from_keyword("/tx-hashes/")
-            .select(&txid.as_byte_array().to_vec())
-            .get_value::<u8>()
-            == 1
+        self.tx_hash_pointer(txid).get().len() != 0
+    }
+
+    /// Add a transaction hash to the used set
+    pub fn add_tx_hash(&self, txid: &Txid) -> Result<()> {
+        self.tx_hash_pointer(txid).set_value::<u8>(0x01);
+        Ok(())
+    }
+
+    /// Get the mint record stored for a transaction, if it minted
+    pub fn mint_record(&self, txid: &Txid) -> Option<MintRecord> {
+        MintRecord::decode(&self.tx_hash_pointer(txid).get()).ok()
+    }
+
+    /// Mark a transaction as used, storing the record of its mint
+    pub fn set_mint_record(&self, txid: &Txid, record: &MintRecord) {
+        self.tx_hash_pointer(txid).set(Arc::new(record.encode()));
+    }
+
+    /// Get the total supply checkpoints
+    pub fn supply_checkpoints(&self) -> CheckpointList {
+        CheckpointList::from_keyword("/checkpoints/supply")
+    }
+
+    /// Get the mint counter checkpoints
+    pub fn minted_checkpoints(&self) -> CheckpointList {
+        CheckpointList::from_keyword("/checkpoints/minted")
+    }
+
+    /// Checkpoint the current total supply and mint counter at `height`
+    pub fn checkpoint_supply(&self, height: u64) -> Result<()> {
+        self.supply_checkpoints()
+            .record(height, self.total_supply())?;
+        self.minted_checkpoints().record(height, self.minted())
+    }
+
+    /// Get the pointer to the mint ledger
+    pub fn mint_ledger_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/mints")
+    }
+
+    /// Get the number of ledger entries
+    pub fn mint_ledger_count(&self) -> u32 {
+        self.mint_ledger_pointer().length()
+    }
+
+    /// Append an entry to the mint ledger, returning its mint index
+    pub fn append_mint_ledger(&self, entry: &LedgerEntry) -> u128 {
+        let index = self.mint_ledger_count() as u128;
+        self.mint_ledger_pointer().append(Arc::new(entry.encode()));
+        index
+    }
+
+    /// Append a mint of `amount` by `tx` to the ledger, returning its mint index
+    pub fn append_mint(&self, tx: &Transaction, vout: u32, height: u64, amount: u128) -> u128 {
+        self.append_mint_ledger(&LedgerEntry {
+            height,
+            txid: tx.compute_txid(),
+            amount,
+            recipient_script_hash: recipient_script_hash(tx, vout),
+        })
+    }
+
+    /// Get up to `count` encoded ledger entries starting at mint index `start`
+    pub fn mint_ledger_range(&self, start: u128, count: u128) -> Vec<u8> {
+        let total = self.mint_ledger_count() as u128;
+        let end = start.saturating_add(count.min(MAX_MINT_RANGE)).min(total);
+        let pointer = self.mint_ledger_pointer();
+        (start.min(end)..end)
+            .flat_map(|i| pointer.select_index(i as u32).get().as_ref().clone())
+            .collect()
+    }
+
+    /// Initialize the token with configuration
+    fn initialize(
+        &self,
+        token_units: u128,
+        value_per_mint: u128,
+        cap: u128,
+        name_part1: u128,
+        name_part2: u128,
+        symbol: u128,
+    ) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        // Prevent multiple initializations
+        self.observe_initialization()
+            .map_err(|_| anyhow!("Contract already initialized"))?;
+
+        // Set configuration
+        self.set_value_per_mint(value_per_mint);
+        self.set_cap(cap);
+
+        // Options in a config envelope take precedence over trailing inputs
+        let config = self.init_config()?;
+
+        // Names and symbols longer than the fixed inputs follow as word lists
+        let (name_words, symbol_index) = string_words(&context, INIT_INPUT_NAME_WORDS)?;
+        let (symbol_words, options_index) = string_words(&context, symbol_index)?;
+        let name = match config.name {
+            Some(name) => Ok(name),
+            None if name_words.is_empty() => TokenName::new(name_part1, name_part2).decode(),
+            None => packing::decode(&name_words),
+        }
+        .map_err(|e| anyhow!("invalid token name: {}", e))?;
+        let symbol = match config.symbol {
+            Some(symbol) => Ok(symbol),
+            None if symbol_words.is_empty() => packing::decode_word(symbol),
+            None => packing::decode(&symbol_words),
+        }
+        .map_err(|e| anyhow!("invalid token symbol: {}", e))?;
+        self.set_long_name_and_symbol(&name, &symbol);
+
+        // Where the data envelope is read from and how large it may be
+        let data_input = match config.data_input {
+            Some(input) => Some(input),
+            None => optional_input(&context, options_index + INIT_OPTION_DATA_INPUT)
+                .map(|input| u32::try_from(input - 1))
+                .transpose()
+                .map_err(|_| anyhow!("data input out of range"))?,
+        };
+        let max_data_len = match config.max_data_len {
+            Some(max_data_len) => max_data_len,
+            None => u64::try_from(
+                optional_input(&context, options_index + INIT_OPTION_MAX_DATA_LEN).unwrap_or(0),
+            )
+            .map_err(|_| anyhow!("data size limit out of range"))?,
+        };
+        self.set_data_envelope_options(data_input, max_data_len)?;
+
+        // MIME types longer than the fixed input follow as a word list
+        let (content_type_words, draft_index) =
+            string_words(&context, options_index + INIT_OPTION_CONTENT_TYPE_WORDS)?;
+
+        // Draft is asked for by the config record or by the input after the content type words
+        let draft =
+            config.draft || optional_input(&context, draft_index + INIT_OPTION_DRAFT).is_some();
+        if draft {
+            self.store_state(LaunchState::Draft, 0);
+        }
+        let content_type = match config.content_type {
+            Some(content_type) => Some(content_type),
+            None if content_type_words.is_empty() => {
+                optional_input(&context, INIT_INPUT_CONTENT_TYPE)
+                    .map(|word| content_type_from_words(&[word]))
+                    .transpose()?
+            }
+            None => Some(content_type_from_words(&content_type_words)?),
+        };
+        let upload = match (config.data_size, config.data_hash) {
+            (Some(size), Some(sha256)) => Some((size, sha256)),
+            _ => match optional_input(&context, INIT_INPUT_DATA_SIZE) {
+                Some(size) => {
+                    let hash_hi = context.inputs.get(INIT_INPUT_DATA_HASH_HI).cloned().unwrap_or(0);
+                    let hash_lo = context.inputs.get(INIT_INPUT_DATA_HASH_LO).cloned().unwrap_or(0);
+                    if hash_hi == 0 && hash_lo == 0 {
+                        return Err(anyhow!("data upload requires the hash of the full segment"));
+                    }
+                    let size =
+                        u64::try_from(size).map_err(|_| anyhow!("data size out of range"))?;
+                    Some((size, root_from_parts(hash_hi, hash_lo)))
+                }
+                None => None,
+            },
+        };
+        match upload {
+            // Larger segments are completed by AppendData and SealData
+            Some((size, sha256)) => {
+                self.check_data_len(size)?;
+                let upload = DataUpload {
+                    size,
+                    sha256,
+                    content_type: content_type.unwrap_or_default(),
+                };
+                self.begin_data_upload(upload, self.witness_data()?)?;
+            }
+            None => {
+                self.set_data()?;
+                self.record_data_info(content_type)?;
+            }
+        }
+
+        let decimals = match config.decimals {
+            Some(decimals) => decimals,
+            None => u8::try_from(
+                context
+                    .inputs
+                    .get(INIT_INPUT_DECIMALS)
+                    .cloned()
+                    .unwrap_or(DEFAULT_DECIMALS as u128),
+            )
+            .map_err(|_| anyhow!("decimals out of range"))?,
+        };
+        self.set_decimals(decimals)?;
+
+        // Mint initial tokens; the premine is the first ledger entry
+        if token_units > 0 {
+            response.alkanes.0.push(self.mint(&context, token_units)?);
+            let tx = consensus_decode::<Transaction>(&mut Cursor::new(self.transaction()))?;
+            self.append_mint(&tx, context.vout, self.height(), token_units);
+            self.checkpoint_supply(self.height())?;
+        }
+
+        response.alkanes.0.push(self.mint_target_token()?);
+
+        // Issue the admin tokens that authorize owner-only opcodes
+        let admin_count = config
+            .admin_count
+            .map(u128::from)
+            .or_else(|| optional_input(&context, INIT_INPUT_ADMIN_COUNT))
+            .unwrap_or(1);
+        let threshold = config
+            .admin_threshold
+            .map(u128::from)
+            .or_else(|| optional_input(&context, INIT_INPUT_ADMIN_THRESHOLD))
+            .unwrap_or(1);
+        if admin_count == 0 || admin_count > MAX_ADMINS {
+            return Err(anyhow!("Between 1 and {} admin tokens are supported", MAX_ADMINS));
+        }
+        if threshold > admin_count {
+            return Err(anyhow!(
+                "Admin threshold {} exceeds admin count {}",
+                threshold,
+                admin_count
+            ));
+        }
+        for _ in 0..admin_count {
+            let auth_token = self.deploy_auth_token()?;
+            self.add_admin_token(&auth_token.id);
+            response.alkanes.0.push(auth_token);
+        }
+        self.set_admin_threshold(threshold);
+
+        let delay = match config.timelock_delay {
+            Some(delay) => delay,
+            None => u64::try_from(optional_input(&context, INIT_INPUT_TIMELOCK_DELAY).unwrap_or(0))
+                .map_err(|_| anyhow!("Timelock delay out of range"))?,
+        };
+        self.set_timelock_delay(delay)?;
+
+        let mut events = vec![Event::Initialized {
+            token_units,
+            value_per_mint,
+            cap,
+        }];
+        if draft {
+            events.push(Event::StateChanged {
+                state: LaunchState::Draft as u8,
+                go_live: 0,
+            });
+        }
+        emit_events(&mut response, &events);
+
+        Ok(response)
+    }
+
+    /// Mint new tokens
+    fn mint_tokens(&self) -> Result<CallResponse> {
+        self.mint_tokens_with(&self.context()?, self.height())
+    }
+
+    /// Mint new tokens for the call in `context` at `height`
+    fn mint_tokens_with(&self, context: &Context, height: u64) -> Result<CallResponse> {
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(&[LaunchState::Live], height)?;
+
+        // Decode the transaction once for its id and recipient
+        let tx = consensus_decode::<Transaction>(&mut Cursor::new(self.transaction()))?;
+        let txid = tx.compute_txid();
+
+        // Refuse mints once the campaign has been closed
+        if self.is_finalized() {
+            return Err(anyhow!("mint finalized"));
+        }
+
+        // Enforce one mint per transaction
+        if self.has_tx_hash(&txid) {
+            return Err(anyhow!("Transaction already used for minting"));
+        }
+
+        // Check if minting would exceed cap
+        if self.minted() >= self.cap() {
+            return Err(anyhow!(
+                "Supply cap reached: {} of {}",
+                self.minted(),
+                self.cap()
+            ));
+        }
+
+        // Mint tokens
+        let value = self.value_per_mint();
+        response.alkanes.0.push(self.mint(context, value)?);
+
+        // Append the mint to the ledger and record its index under the txid
+        let index = self.append_mint(&tx, context.vout, height, value);
+        self.set_mint_record(
+            &txid,
+            &MintRecord {
+                index,
+                height,
+                amount: value,
+            },
+        );
+
+        // Increment mint counter
+        self.increment_mint()?;
+        self.checkpoint_supply(height)?;
+
+        let mut events = vec![Event::Minted {
+            index,
+            amount: value,
+        }];
+
+        // Finalize automatically once the cap is reached
+        if self.minted() >= self.cap() {
+            self.set_finalization(height, &txid);
+            events.push(Event::Finalized {
+                height,
+                minted: self.minted(),
+            });
+        }
+
+        emit_events(&mut response, &events);
+
+        Ok(response)
+    }
+
+    /// Mint a custom amount to an approved minter contract
+    fn mint_to(&self, amount: u128) -> Result<CallResponse> {
+        self.mint_to_with(&self.context()?, amount, self.height())
+    }
+
+    /// Mint `amount` to the minter calling in `context` at `height`
+    fn mint_to_with(&self, context: &Context, amount: u128, height: u64) -> Result<CallResponse> {
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(&[LaunchState::Live], height)?;
+
+        if self.is_finalized() {
+            return Err(anyhow!("mint finalized"));
+        }
+
+        // Minters are other contracts, identified by the calling AlkaneId
+        let minter = context.caller.clone();
+        self.use_minter_allowance(&minter, amount)?;
+
+        response.alkanes.0.push(self.mint(context, amount)?);
+        self.append_mint_ledger(&LedgerEntry {
+            height,
+            txid: context.transaction_id()?,
+            amount,
+            recipient_script_hash: minter_recipient_hash(&minter),
+        });
+        self.checkpoint_supply(height)?;
+
+        emit_events(&mut response, &[Event::MintedTo { id: minter, amount }]);
+
+        Ok(response)
+    }
+
+    /// Close minting early, freezing the cap at the current mint count
+    fn finalize(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        if self.is_finalized() {
+            return Err(anyhow!("mint finalized"));
+        }
+
+        let txid = context.transaction_id()?;
+        let height = self.height();
+        self.set_finalization(height, &txid);
+
+        emit_events(
+            &mut response,
+            &[Event::Finalized {
+                height,
+                minted: self.minted(),
+            }],
+        );
+
+        Ok(response)
+    }
+
+    /// Get the token decimals
+    fn get_decimals(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = vec![self.decimals()];
+
+        Ok(response)
+    }
+
+    /// Collect the token state served by GetTokenInfo
+    pub fn token_info(&self, height: u64) -> TokenInfo {
+        TokenInfo {
+            name: self.name(),
+            symbol: self.symbol(),
+            total_supply: self.total_supply(),
+            cap: self.cap(),
+            minted: self.minted(),
+            value_per_mint: self.value_per_mint(),
+            decimals: self.decimals(),
+            // Minting is paused whenever the launch state isn't Live
+            paused: !self
+                .launch_state(height)
+                .is_ok_and(|state| state == LaunchState::Live),
+            finalized: self.is_finalized(),
+            attributes_frozen: self.attributes_frozen(),
+            data_len: self.data_len() as u64,
+            attributes: self.attributes(),
+        }
+    }
+
+    /// Get all token state in one versioned binary layout
+    fn get_token_info(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.token_info(self.height()).encode();
+
+        Ok(response)
+    }
+
+    /// Get all token state as a JSON object
+    fn get_token_info_json(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.token_info(self.height()).to_json().into_bytes();
+
+        Ok(response)
+    }
+
+    /// Get whether a txid minted, with its mint record
+    fn get_mint_by_txid(&self, txid_hi: u128, txid_lo: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = match self.mint_record(&txid_from_parts(txid_hi, txid_lo)) {
+            Some(record) => {
+                let mut data = vec![0x01];
+                data.extend(record.encode());
+                data
+            }
+            None => vec![0x00],
+        };
+
+        Ok(response)
+    }
+
+    /// Get encoded mint ledger entries
+    fn get_mint_range(&self, start: u128, count: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.mint_ledger_range(start, count);
+
+        Ok(response)
+    }
+
+    /// Get the number of mint ledger entries
+    fn get_mint_count(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = (self.mint_ledger_count() as u128).to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Get the total supply as of a block height
+    fn get_total_supply_at(&self, height: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let height = u64::try_from(height).unwrap_or(u64::MAX);
+        response.data = self
+            .supply_checkpoints()
+            .value_at(height)?
+            .to_le_bytes()
+            .to_vec();
+
+        Ok(response)
+    }
+
+    /// Get the mint counter as of a block height
+    fn get_minted_at(&self, height: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let height = u64::try_from(height).unwrap_or(u64::MAX);
+        response.data = self
+            .minted_checkpoints()
+            .value_at(height)?
+            .to_le_bytes()
+            .to_vec();
+
+        Ok(response)
+    }
+
+    /// Get the finalization record as height (u64 LE) followed by the txid
+    fn get_finalization(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.finalization_pointer().get().as_ref().clone();
+
+        Ok(response)
+    }
+
+    /// Check that a change can be applied to the current state
+    fn validate_change(&self, action: u128, value: u128) -> Result<()> {
+        match action {
+            ACTION_SET_VALUE_PER_MINT => Ok(()),
+            ACTION_SET_CAP => {
+                if self.is_finalized() {
+                    return Err(anyhow!("mint finalized"));
+                }
+                let cap = if value == 0 { u128::MAX } else { value };
+                if cap < self.minted() {
+                    return Err(anyhow!(
+                        "Cap {} is below the mint count {}",
+                        cap,
+                        self.minted()
+                    ));
+                }
+                Ok(())
+            }
+            ACTION_SET_TIMELOCK_DELAY => {
+                let delay =
+                    u64::try_from(value).map_err(|_| anyhow!("Timelock delay out of range"))?;
+                if delay < self.timelock_delay() {
+                    return Err(anyhow!("Timelock delay can only be increased"));
+                }
+                Ok(())
+            }
+            _ => Err(anyhow!("Unknown change action {}", action)),
+        }
+    }
+
+    /// Apply an executed change
+    fn apply_change(&self, change: &QueuedChange) -> Result<()> {
+        self.validate_change(change.action, change.value)?;
+        match change.action {
+            ACTION_SET_VALUE_PER_MINT => self.set_value_per_mint(change.value),
+            ACTION_SET_CAP => self.set_cap(change.value),
+            ACTION_SET_TIMELOCK_DELAY => self.set_timelock_delay(change.value as u64)?,
+            _ => unreachable!("validated above"),
+        }
+        Ok(())
+    }
+
+    /// Queue a timelocked parameter change
+    fn queue_change(&self, action: u128, value: u128) -> Result<CallResponse> {
+        self.queue_change_with(&self.context()?, action, value, self.height())
+    }
+
+    /// Queue a parameter change for the call in `context` at `height`, or
+    /// apply it at once while the token is in Draft
+    fn queue_change_with(
+        &self,
+        context: &Context,
+        action: u128,
+        value: u128,
+        height: u64,
+    ) -> Result<CallResponse> {
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        let state = self.require_state(OPEN_STATES, height)?;
+
+        self.only_owner(context)?;
+        self.validate_change(action, value)?;
+
+        // Draft is the configuration phase, so changes skip the timelock
+        if state == LaunchState::Draft {
+            self.apply_change(&QueuedChange {
+                action,
+                value,
+                eta: height,
+                status: ChangeStatus::Executed,
+            })?;
+            emit_events(&mut response, &[Event::ParamsChanged { action, value }]);
+            return Ok(response);
+        }
+
+        let id = self.enqueue_change(action, value, height)?;
+        response.data = (id as u128).to_le_bytes().to_vec();
+
+        emit_events(
+            &mut response,
+            &[Event::ChangeQueued {
+                change_id: id,
+                action,
+                value,
+                eta: self.queued_change(id)?.eta,
+            }],
+        );
+
+        Ok(response)
+    }
+
+    /// Execute a queued change once its delay has passed
+    fn execute_change(&self, change_id: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        let change = self.take_executable_change(change_id_to_u32(change_id)?, self.height())?;
+        self.apply_change(&change)?;
+
+        emit_events(
+            &mut response,
+            &[Event::ParamsChanged {
+                action: change.action,
+                value: change.value,
+            }],
+        );
+
+        Ok(response)
+    }
+
+    /// Cancel a pending queued change
+    fn cancel_change(&self, change_id: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+        let change_id = change_id_to_u32(change_id)?;
+        self.close_change(change_id, ChangeStatus::Cancelled)?;
+
+        emit_events(&mut response, &[Event::ChangeCancelled { change_id }]);
+
+        Ok(response)
+    }
+
+    /// Get the timelock delay in blocks
+    fn get_timelock_delay(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.timelock_delay().to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Get the number of changes ever queued
+    fn get_queued_change_count(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = (self.queued_change_count() as u128).to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Get an encoded queued change
+    fn get_queued_change(&self, change_id: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.queued_change(change_id_to_u32(change_id)?)?.encode();
+
+        Ok(response)
+    }
+
+    /// Get the ids of all pending changes
+    fn get_pending_changes(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self
+            .pending_changes()
+            .into_iter()
+            .flat_map(|id| id.to_le_bytes())
+            .collect();
+
+        Ok(response)
+    }
+
+    /// Burn this token sent along, refunding any other alkanes
+    fn burn(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::default();
+
+        self.require_state(ANY_STATE, self.height())?;
+
+        let mut value = 0u128;
+        for transfer in context.incoming_alkanes.0.iter() {
+            if transfer.id == context.myself {
+                value = overflow_error(value.checked_add(transfer.value))?;
+            } else {
+                response.alkanes.0.push(transfer.clone());
+            }
+        }
+        if value == 0 {
+            return Err(anyhow!("No tokens sent to burn"));
+        }
+        self.record_burn(value)?;
+        self.checkpoint_supply(self.height())?;
+
+        emit_events(&mut response, &[Event::Burned { amount: value }]);
+
+        Ok(response)
+    }
+
+    /// Get the running total of burned tokens
+    fn get_burned(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.burned().to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Set an airdrop root and fund its pool with this token sent along
+    fn set_airdrop(&self, root_hi: u128, root_lo: u128, expiry: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::default();
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        // Keep this token as the pool and refund everything else
+        let mut funding = 0u128;
+        for transfer in context.incoming_alkanes.0.iter() {
+            if transfer.id == context.myself {
+                funding = overflow_error(funding.checked_add(transfer.value))?;
+            } else {
+                response.alkanes.0.push(transfer.clone());
+            }
+        }
+        if funding == 0 {
+            return Err(anyhow!("No tokens sent to fund the airdrop"));
+        }
+        let expiry = u64::try_from(expiry).map_err(|_| anyhow!("Airdrop expiry out of range"))?;
+        if expiry < self.height() {
+            return Err(anyhow!("Airdrop expiry {} has already passed", expiry));
+        }
+        self.open_airdrop(root_from_parts(root_hi, root_lo), expiry, funding)?;
+
+        emit_events(
+            &mut response,
+            &[Event::AirdropFunded {
+                amount: funding,
+                expiry,
+            }],
+        );
+
+        Ok(response)
+    }
+
+    /// Claim an airdrop leaf for the recipient output of this transaction
+    fn claim_airdrop(&self, amount: u128) -> Result<CallResponse> {
+        self.claim_airdrop_with(&self.context()?, amount, self.height())
+    }
+
+    /// Claim an airdrop leaf for the call in `context` at `height`
+    fn claim_airdrop_with(
+        &self,
+        context: &Context,
+        amount: u128,
+        height: u64,
+    ) -> Result<CallResponse> {
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(&[LaunchState::Live], height)?;
+
+        // The payout returns to the caller, so only the transaction itself may
+        // claim; a contract in between would receive the recipient's leaf
+        if context.caller != AlkaneId::default() {
+            return Err(anyhow!("ClaimAirdrop must be called directly by the transaction"));
+        }
+
+        let tx = consensus_decode::<Transaction>(&mut Cursor::new(self.transaction()))?;
+        let script =
+            recipient_script(&tx, context.vout).ok_or_else(|| anyhow!("No recipient output"))?;
+        // The proof travels in an envelope chosen the same way as the token data
+        let proof = envelope_payload(&tx, self.data_input()).unwrap_or_default();
+        let amount = self.claim_airdrop_leaf(script.as_bytes(), amount, &proof, height)?;
+        response.alkanes.0.push(AlkaneTransfer {
+            id: context.myself.clone(),
+            value: amount,
+        });
+
+        emit_events(&mut response, &[Event::AirdropClaimed { amount }]);
+
+        Ok(response)
+    }
+
+    /// Reclaim unclaimed airdrop funds after expiry
+    fn reclaim_airdrop(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(ANY_STATE, self.height())?;
+
+        self.only_owner(&context)?;
+
+        let amount = self.reclaim_airdrop_pool(self.height())?;
+        if amount > 0 {
+            response.alkanes.0.push(AlkaneTransfer {
+                id: context.myself.clone(),
+                value: amount,
+            });
+        }
+
+        emit_events(&mut response, &[Event::AirdropReclaimed { amount }]);
+
+        Ok(response)
+    }
+
+    /// Append the transaction's witness payload to a pending data upload
+    fn append_data(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        let len = self.append_data_chunk(&self.witness_data()?)?;
+
+        emit_events(&mut response, &[Event::DataAppended { len }]);
+
+        Ok(response)
+    }
+
+    /// Drop the chunks of the pending data upload
+    fn abort_data_upload(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        self.discard_data_chunks()?;
+
+        emit_events(&mut response, &[Event::DataUploadAborted]);
+
+        Ok(response)
+    }
+
+    /// Verify and lock the uploaded data segment
+    fn seal_data(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        let len = self.seal_data_upload(&context.transaction_id()?, self.height())?;
+
+        emit_events(&mut response, &[Event::DataSealed { len }]);
+
+        Ok(response)
+    }
+
+    /// Set a metadata attribute
+    fn set_attribute(&self, key: u128, value: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        let value = if value == 0 {
+            self.witness_data()?
+        } else {
+            packing::decode_bytes(&[value])
+        };
+        self.store_attribute(key, value)?;
+
+        emit_events(&mut response, &[Event::AttributeSet { key }]);
+
+        Ok(response)
     }
 
-    /// Add a transaction hash to the used set
-    pub fn add_tx_hash(&self, txid: &Txid) -> Result<()> {
-        StoragePointer::from_keyword("/tx-hashes/")
-            .select(&txid.as_byte_array().to_vec())
-            .set_value::<u8>(0x01);
-        Ok(())
+    /// Freeze the metadata attributes
+    fn freeze_attributes(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        self.freeze_attribute_map()?;
+
+        emit_events(&mut response, &[Event::AttributesFrozen]);
+
+        Ok(response)
     }
 
-    /// Initialize the token with configuration
-    fn initialize(
+    /// Point the token data at an existing inscription
+    fn set_inscription_reference(
         &self,
-        token_units: u128,
-        value_per_mint: u128,
-        cap: u128,
-        name_part1: u128,
-        name_part2: u128,
-        symbol: u128,
+        txid_hi: u128,
+        txid_lo: u128,
+        index: u128,
     ) -> Result<CallResponse> {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
-        // Prevent multiple initializations
-        self.observe_initialization()
-            .map_err(|_| anyhow!("Contract already initialized"))?;
+        self.require_state(OPEN_STATES, self.height())?;
 
-        // Set configuration
-        self.set_value_per_mint(value_per_mint);
-        self.set_cap(cap);
-        self.set_data()?;
+        self.only_owner(&context)?;
 
-        // Create TokenName from the two parts
-        let name = TokenName::new(name_part1, name_part2);
-        <Self as MintableToken>::set_name_and_symbol(self, name, symbol);
+        self.set_data_reference(&DataReference::Inscription {
+            txid: txid_from_parts(txid_hi, txid_lo),
+            index: u32::try_from(index).map_err(|_| anyhow!("inscription index out of range"))?,
+        })?;
 
-        // Mint initial tokens
-        if token_units > 0 {
-            response.alkanes.0.push(self.mint(&context, token_units)?);
-        }
+        emit_events(&mut response, &[Event::DataReferenceSet]);
 
-        response.alkanes.0.push(self.mint_target_token()?);
+        Ok(response)
+    }
+
+    /// Point the token data at the URI in the witness payload
+    fn set_uri_reference(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        self.set_data_reference(&DataReference::uri(self.witness_data()?)?)?;
+
+        emit_events(&mut response, &[Event::DataReferenceSet]);
 
         Ok(response)
     }
 
-    /// Mint new tokens
-    fn mint_tokens(&self) -> Result<CallResponse> {
+    /// Fix the go-live height of a Draft token
+    fn schedule_launch(&self, height: u128) -> Result<CallResponse> {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
-        // Get transaction ID
-        let txid = context.transaction_id()?;
+        self.require_state(&[LaunchState::Draft], self.height())?;
 
-        // Enforce one mint per transaction
-        if self.has_tx_hash(&txid) {
-            return Err(anyhow!("Transaction already used for minting"));
+        self.only_owner(&context)?;
+
+        let go_live = u64::try_from(height).map_err(|_| anyhow!("go-live height out of range"))?;
+        self.schedule_go_live(go_live, self.height())?;
+
+        emit_events(
+            &mut response,
+            &[Event::StateChanged {
+                state: LaunchState::Scheduled as u8,
+                go_live,
+            }],
+        );
+
+        Ok(response)
+    }
+
+    /// Close the token for good
+    fn close(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        self.close_lifecycle(self.height())?;
+
+        emit_events(
+            &mut response,
+            &[Event::StateChanged {
+                state: LaunchState::Closed as u8,
+                go_live: self.go_live_height()?,
+            }],
+        );
+
+        Ok(response)
+    }
+
+    /// Get the launch state and go-live height
+    fn get_state(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let mut data = vec![self.launch_state(self.height())? as u8];
+        data.extend_from_slice(&self.go_live_height()?.to_le_bytes());
+        response.data = data;
+
+        Ok(response)
+    }
+
+    /// Get a metadata attribute value
+    fn get_attribute(&self, key: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let key =
+            packing::decode_word(key).map_err(|e| anyhow!("invalid attribute key: {}", e))?;
+        response.data = self.attribute(&key);
+
+        Ok(response)
+    }
+
+    /// Get the metadata attribute keys
+    fn list_attribute_keys(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = encode_keys(&self.attribute_keys());
+
+        Ok(response)
+    }
+
+    /// Get the airdrop root, expiry and unclaimed pool
+    fn get_airdrop(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let mut data = self.airdrop_root().unwrap_or([0u8; 32]).to_vec();
+        data.extend_from_slice(&self.airdrop_expiry().to_le_bytes());
+        data.extend_from_slice(&self.airdrop_remaining().to_le_bytes());
+        response.data = data;
+
+        Ok(response)
+    }
+
+    /// Keep the incoming alkanes in the treasury, returning admin tokens
+    fn deposit(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::default();
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        let admins = self.admin_tokens();
+        let mut events = Vec::new();
+        for transfer in context.incoming_alkanes.0.iter() {
+            if admins.contains(&transfer.id) {
+                response.alkanes.0.push(transfer.clone());
+            } else {
+                self.credit_treasury(&transfer.id, transfer.value)?;
+                events.push(Event::Deposited {
+                    id: transfer.id,
+                    amount: transfer.value,
+                });
+            }
         }
 
-        // Check if minting would exceed cap
-        if self.minted() >= self.cap() {
+        emit_events(&mut response, &events);
+
+        Ok(response)
+    }
+
+    /// Withdraw alkanes held by the treasury
+    fn withdraw(&self, block: u128, tx: u128, amount: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(ANY_STATE, self.height())?;
+
+        self.only_owner(&context)?;
+
+        // The books must never claim more than the contract actually holds
+        let id = AlkaneId::new(block, tx);
+        let held = self.balance(&context.myself, &id);
+        if self.treasury_balance(&id) > held {
             return Err(anyhow!(
-                "Supply cap reached: {} of {}",
-                self.minted(),
-                self.cap()
+                "Treasury books out of sync: recorded {} but holding {}",
+                self.treasury_balance(&id),
+                held
             ));
         }
+        self.debit_treasury(&id, amount)?;
+        response.alkanes.0.push(AlkaneTransfer { id, value: amount });
 
-        // Record transaction hash
-        self.add_tx_hash(&txid)?;
+        emit_events(&mut response, &[Event::Withdrawn { id, amount }]);
 
-        // Mint tokens
-        let value = self.value_per_mint();
-        response.alkanes.0.push(self.mint(&context, value)?);
+        Ok(response)
+    }
 
-        // Increment mint counter
-        self.increment_mint()?;
+    /// Set the lifetime allowance of a minter contract
+    fn set_minter(&self, block: u128, tx: u128, allowance: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(OPEN_STATES, self.height())?;
+
+        self.only_owner(&context)?;
+
+        let id = AlkaneId::new(block, tx);
+        self.set_minter_allowance(&id, allowance)?;
+
+        emit_events(&mut response, &[Event::MinterSet { id, allowance }]);
+
+        Ok(response)
+    }
+
+    /// Get every listed minter with its allowance and usage
+    fn get_minters(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let mut data = Vec::new();
+        for id in self.minter_ids() {
+            let bytes: Vec<u8> = id.clone().into();
+            data.extend(bytes);
+            data.extend_from_slice(&self.minter_allowance(&id).to_le_bytes());
+            data.extend_from_slice(&self.minter_used(&id).to_le_bytes());
+        }
+        response.data = data;
+
+        Ok(response)
+    }
+
+    /// Get the recorded and held treasury balances
+    fn get_treasury(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let mut data = Vec::new();
+        for id in self.treasury_ids() {
+            let bytes: Vec<u8> = id.clone().into();
+            data.extend(bytes);
+            data.extend_from_slice(&self.treasury_balance(&id).to_le_bytes());
+            data.extend_from_slice(&self.balance(&context.myself, &id).to_le_bytes());
+        }
+        response.data = data;
+
+        Ok(response)
+    }
+
+    /// Approve an admin call with the admin tokens sent along
+    fn approve(&self, proposal_id: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(ANY_STATE, self.height())?;
+
+        let signers = self.presented_admins(&context);
+        if signers.is_empty() {
+            return Err(anyhow!("Auth token is not in incoming alkanes"));
+        }
+        let mut events = Vec::new();
+        for admin in signers {
+            self.record_approval(proposal_id, admin)?;
+            events.push(Event::Approved { proposal_id, admin });
+        }
+
+        emit_events(&mut response, &events);
+
+        Ok(response)
+    }
+
+    /// Get a proposal's approval count and approving admin indices
+    fn get_proposal(&self, proposal_id: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let approvals = self.approvals(proposal_id);
+        let mut data = (approvals.len() as u128).to_le_bytes().to_vec();
+        for admin in approvals {
+            data.extend_from_slice(&admin.to_le_bytes());
+        }
+        response.data = data;
+
+        Ok(response)
+    }
+
+    /// Get the admin threshold and the admin token ids
+    fn get_admins(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        let mut data = self.admin_threshold().to_le_bytes().to_vec();
+        for admin in self.admin_tokens() {
+            let bytes: Vec<u8> = admin.into();
+            data.extend(bytes);
+        }
+        response.data = data;
 
         Ok(response)
     }
@@ -589,6 +2646,23 @@ impl MintableAlkane {
         }
     }
 
+    /// Deploy an auth token that grants admin access to this contract
+    fn deploy_auth_token(&self) -> Result<AlkaneTransfer> {
+        let cellpack = Cellpack {
+            target: AlkaneId {
+                block: 6,
+                tx: ALKANE_FACTORY_OWNED_TOKEN_ID,
+            },
+            inputs: vec![0x0, 1],
+        };
+        let response = self.call(&cellpack, &AlkaneTransferParcel::default(), self.fuel())?;
+        if response.alkanes.0.len() < 1 {
+            Err(anyhow!("auth token not returned with factory"))
+        } else {
+            Ok(response.alkanes.0[0])
+        }
+    }
+
     /// Set the token name and symbol
     fn set_name_and_symbol(
         &self,
@@ -611,6 +2685,8 @@ impl MintableAlkane {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
+        self.require_state(VIEW_STATES, self.height())?;
+
         response.data = self.name().into_bytes().to_vec();
 
         Ok(response)
@@ -621,6 +2697,8 @@ impl MintableAlkane {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
+        self.require_state(VIEW_STATES, self.height())?;
+
         response.data = self.symbol().into_bytes().to_vec();
 
         Ok(response)
@@ -631,6 +2709,8 @@ impl MintableAlkane {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
+        self.require_state(VIEW_STATES, self.height())?;
+
         response.data = self.total_supply().to_le_bytes().to_vec();
 
         Ok(response)
@@ -641,6 +2721,8 @@ impl MintableAlkane {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
+        self.require_state(VIEW_STATES, self.height())?;
+
         response.data = self.cap().to_le_bytes().to_vec();
 
         Ok(response)
@@ -651,6 +2733,8 @@ impl MintableAlkane {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
+        self.require_state(VIEW_STATES, self.height())?;
+
         response.data = self.minted().to_le_bytes().to_vec();
 
         Ok(response)
@@ -661,6 +2745,8 @@ impl MintableAlkane {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
+        self.require_state(VIEW_STATES, self.height())?;
+
         response.data = self.value_per_mint().to_le_bytes().to_vec();
 
         Ok(response)
@@ -671,10 +2757,114 @@ impl MintableAlkane {
         let context = self.context()?;
         let mut response = CallResponse::forward(&context.incoming_alkanes);
 
+        self.require_state(VIEW_STATES, self.height())?;
+
+        self.require_data_available()?;
+
         response.data = self.data();
 
         Ok(response)
     }
+
+    /// Get the data segment MIME type, encoding, sizes and hash
+    fn get_data_info(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        self.require_data_available()?;
+
+        response.data = self.data_info_pointer().get().as_ref().clone();
+
+        Ok(response)
+    }
+
+    /// Get the token data exactly as stored
+    fn get_raw_data(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        self.require_data_available()?;
+
+        response.data = self.raw_data();
+
+        Ok(response)
+    }
+
+    /// Get a slice of the decompressed token data
+    fn get_data_range(&self, offset: u128, length: u128) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        self.require_data_available()?;
+
+        response.data = self.data_range(offset, length);
+
+        Ok(response)
+    }
+
+    /// Get the length of the decompressed token data
+    fn get_data_length(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        self.require_data_available()?;
+
+        response.data = self.data_len().to_le_bytes().to_vec();
+
+        Ok(response)
+    }
+
+    /// Get the latest data hash commitment
+    fn get_data_hash(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        self.require_data_available()?;
+
+        response.data = self
+            .data_commitment()
+            .map(|commitment| commitment.encode())
+            .unwrap_or_default();
+
+        Ok(response)
+    }
+
+    /// Get the inscription or URI the token data lives at
+    fn get_data_reference(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self
+            .data_reference()?
+            .map(|reference| reference.encode())
+            .unwrap_or_default();
+
+        Ok(response)
+    }
+
+    /// Get every data hash commitment
+    fn get_data_hash_history(&self) -> Result<CallResponse> {
+        let context = self.context()?;
+        let mut response = CallResponse::forward(&context.incoming_alkanes);
+
+        self.require_state(VIEW_STATES, self.height())?;
+
+        response.data = self.data_hash_history();
+
+        Ok(response)
+    }
 }
 
 // Use the MessageDispatch macro for opcode handling
@@ -0,0 +1,134 @@
+//! Launch lifecycle: Draft → Scheduled → Live → Closed
+//!
+//! A token can start in Draft, where the admins finish configuring it and
+//! nobody can mint. Parameter changes apply at once in Draft instead of
+//! waiting on the timelock; from Scheduled on they are timelocked. Scheduling
+//! fixes the go-live height, from which the token is Live and mints are
+//! accepted. Closed is terminal. Deployments that predate the lifecycle, and
+//! tokens initialized without asking for Draft, are Live.
+//!
+//! Every opcode checks the state. Views are allowed in every state.
+
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// State of the launch lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchState {
+    /// Being configured, no mints
+    Draft = 0,
+    /// Go-live height fixed but not reached yet, no mints
+    Scheduled = 1,
+    /// Mints accepted
+    Live = 2,
+    /// Terminal
+    Closed = 3,
+}
+
+impl TryFrom<u8> for LaunchState {
+    type Error = anyhow::Error;
+
+    fn try_from(v: u8) -> Result<Self> {
+        match v {
+            0 => Ok(LaunchState::Draft),
+            1 => Ok(LaunchState::Scheduled),
+            2 => Ok(LaunchState::Live),
+            3 => Ok(LaunchState::Closed),
+            _ => Err(anyhow!("unknown launch state {}", v)),
+        }
+    }
+}
+
+/// States in which the token has not been closed
+pub const OPEN_STATES: &[LaunchState] = &[
+    LaunchState::Draft,
+    LaunchState::Scheduled,
+    LaunchState::Live,
+];
+
+/// Every state
+pub const ANY_STATE: &[LaunchState] = &[
+    LaunchState::Draft,
+    LaunchState::Scheduled,
+    LaunchState::Live,
+    LaunchState::Closed,
+];
+
+/// States in which views are served: all of them
+pub const VIEW_STATES: &[LaunchState] = ANY_STATE;
+
+/// Lifecycle trait provides the stored state and its transitions
+pub trait Lifecycle: AlkaneResponder {
+    /// Get the pointer to the stored state (u8) and go-live height (u64)
+    fn state_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/state")
+    }
+
+    /// Get the stored state and go-live height; Live when nothing is stored
+    fn stored_state(&self) -> Result<(LaunchState, u64)> {
+        let bytes = self.state_pointer().get();
+        if bytes.len() == 0 {
+            return Ok((LaunchState::Live, 0));
+        }
+        if bytes.len() != 9 {
+            return Err(anyhow!("invalid launch state length {}", bytes.len()));
+        }
+        Ok((
+            LaunchState::try_from(bytes[0])?,
+            u64::from_le_bytes(bytes[1..9].try_into()?),
+        ))
+    }
+
+    /// Get the state at `height`, a scheduled launch being Live once it is reached
+    fn launch_state(&self, height: u64) -> Result<LaunchState> {
+        Ok(match self.stored_state()? {
+            (LaunchState::Scheduled, go_live) if height >= go_live => LaunchState::Live,
+            (state, _) => state,
+        })
+    }
+
+    /// Get the go-live height, 0 when none was scheduled
+    fn go_live_height(&self) -> Result<u64> {
+        Ok(self.stored_state()?.1)
+    }
+
+    /// Store a state and go-live height
+    fn store_state(&self, state: LaunchState, go_live: u64) {
+        let mut bytes = vec![state as u8];
+        bytes.extend_from_slice(&go_live.to_le_bytes());
+        self.state_pointer().set(Arc::new(bytes));
+    }
+
+    /// Refuse an opcode outside the states it is allowed in
+    fn require_state(&self, allowed: &[LaunchState], height: u64) -> Result<LaunchState> {
+        let state = self.launch_state(height)?;
+        if !allowed.contains(&state) {
+            return Err(anyhow!("not allowed while the token is {:?}", state));
+        }
+        Ok(state)
+    }
+
+    /// Move from Draft to Scheduled with a go-live height after `height`
+    fn schedule_go_live(&self, go_live: u64, height: u64) -> Result<()> {
+        self.require_state(&[LaunchState::Draft], height)?;
+        if go_live <= height {
+            return Err(anyhow!(
+                "go-live height {} must be after the current height {}",
+                go_live,
+                height
+            ));
+        }
+        self.store_state(LaunchState::Scheduled, go_live);
+        Ok(())
+    }
+
+    /// Move to the terminal Closed state
+    fn close_lifecycle(&self, height: u64) -> Result<()> {
+        self.require_state(OPEN_STATES, height)?;
+        self.store_state(LaunchState::Closed, self.go_live_height()?);
+        Ok(())
+    }
+}
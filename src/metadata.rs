@@ -0,0 +1,124 @@
+//! Key/value metadata attributes
+//!
+//! Attributes such as a website, socials, a description or license terms
+//! are kept under the `/meta/` prefix. Keys are short strings packed into a
+//! single u128 (see `packing`); values are arbitrary bytes, usually UTF-8.
+//! The admins can freeze the map, after which it can no longer change.
+
+use crate::codec::Reader;
+use crate::packing;
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// Most distinct attribute keys
+pub const MAX_ATTRIBUTES: u32 = 64;
+/// Largest attribute value in bytes
+pub const MAX_ATTRIBUTE_LEN: usize = 4096;
+
+/// Encode attribute keys as u32 length-prefixed strings, as served by
+/// `ListAttributeKeys`
+pub fn encode_keys(keys: &[String]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for key in keys {
+        bytes.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(key.as_bytes());
+    }
+    bytes
+}
+
+/// Decode a `ListAttributeKeys` response
+pub fn decode_keys(bytes: &[u8]) -> Result<Vec<String>> {
+    let mut reader = Reader::new(bytes);
+    let mut keys = Vec::new();
+    while !reader.is_empty() {
+        keys.push(reader.string()?);
+    }
+    Ok(keys)
+}
+
+/// Metadata trait provides the attribute map and its freeze switch
+pub trait Metadata: AlkaneResponder {
+    /// Get the pointer to the list of attribute keys, in insertion order
+    fn attribute_keys_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/meta/keys")
+    }
+
+    /// Get the attribute keys in insertion order
+    fn attribute_keys(&self) -> Vec<String> {
+        let pointer = self.attribute_keys_pointer();
+        (0..pointer.length())
+            .map(|i| String::from_utf8_lossy(&pointer.select_index(i).get()).into_owned())
+            .collect()
+    }
+
+    /// Get the pointer to an attribute value
+    fn attribute_pointer(&self, key: &str) -> StoragePointer {
+        StoragePointer::from_keyword("/meta/values/").select(&key.as_bytes().to_vec())
+    }
+
+    /// Get an attribute value, empty when unset
+    fn attribute(&self, key: &str) -> Vec<u8> {
+        self.attribute_pointer(key).get().as_ref().clone()
+    }
+
+    /// Get every attribute as key/value pairs in insertion order
+    fn attributes(&self) -> Vec<(String, Vec<u8>)> {
+        self.attribute_keys()
+            .into_iter()
+            .map(|key| {
+                let value = self.attribute(&key);
+                (key, value)
+            })
+            .collect()
+    }
+
+    /// Get the pointer to the freeze flag
+    fn attributes_frozen_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/meta/frozen")
+    }
+
+    /// Check whether the attributes have been frozen
+    fn attributes_frozen(&self) -> bool {
+        self.attributes_frozen_pointer().get_value::<u8>() == 1
+    }
+
+    /// Freeze the attributes for good
+    fn freeze_attribute_map(&self) -> Result<()> {
+        if self.attributes_frozen() {
+            return Err(anyhow!("attributes already frozen"));
+        }
+        self.attributes_frozen_pointer().set_value::<u8>(0x01);
+        Ok(())
+    }
+
+    /// Set an attribute from its packed key, returning the decoded key
+    fn store_attribute(&self, key: u128, value: Vec<u8>) -> Result<String> {
+        if self.attributes_frozen() {
+            return Err(anyhow!("attributes are frozen"));
+        }
+        let key =
+            packing::decode_word(key).map_err(|e| anyhow!("invalid attribute key: {}", e))?;
+        if key.is_empty() {
+            return Err(anyhow!("attribute key is empty"));
+        }
+        if value.len() > MAX_ATTRIBUTE_LEN {
+            return Err(anyhow!(
+                "attribute value of {} bytes exceeds {} bytes",
+                value.len(),
+                MAX_ATTRIBUTE_LEN
+            ));
+        }
+        if !self.attribute_keys().contains(&key) {
+            if self.attribute_keys_pointer().length() >= MAX_ATTRIBUTES {
+                return Err(anyhow!("at most {} attributes are supported", MAX_ATTRIBUTES));
+            }
+            self.attribute_keys_pointer()
+                .append(Arc::new(key.as_bytes().to_vec()));
+        }
+        self.attribute_pointer(&key).set(Arc::new(value));
+        Ok(key)
+    }
+}
@@ -0,0 +1,105 @@
+//! Delegated minting by other alkane contracts
+//!
+//! The admins keep an allowlist of minter AlkaneIds, each with a lifetime
+//! allowance. A listed contract calling `MintTo` mints any amount up to what
+//! is left of its allowance, outside the one-mint-per-transaction rule of
+//! `MintTokens`. Setting an allowance of 0 revokes a minter; its usage is
+//! kept, so lowering an allowance below the usage also stops it.
+//!
+//! Allowances sit outside the mint cap: the cap bounds `MintTokens` only, so
+//! the supply can exceed the premine plus cap times value per mint by the
+//! sum of the allowances. Delegated mints are still recorded in the ledger.
+
+use crate::alkane_id_from_bytes;
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::utils::overflow_error;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// Most distinct minters ever listed
+pub const MAX_MINTERS: u32 = 64;
+
+/// Minters trait provides the minter allowlist and per-minter usage
+pub trait Minters: AlkaneResponder {
+    /// Get the pointer to the list of AlkaneIds ever listed as minters
+    fn minter_ids_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/minters/ids")
+    }
+
+    /// Get the AlkaneIds ever listed as minters, revoked ones included
+    fn minter_ids(&self) -> Vec<AlkaneId> {
+        let pointer = self.minter_ids_pointer();
+        (0..pointer.length())
+            .filter_map(|i| alkane_id_from_bytes(&pointer.select_index(i).get()).ok())
+            .collect()
+    }
+
+    /// Get the pointer to the allowance of a minter
+    fn minter_allowance_pointer(&self, id: &AlkaneId) -> StoragePointer {
+        let bytes: Vec<u8> = id.clone().into();
+        StoragePointer::from_keyword("/minters/allowances/").select(&bytes)
+    }
+
+    /// Get the lifetime allowance of a minter, 0 when not listed
+    fn minter_allowance(&self, id: &AlkaneId) -> u128 {
+        self.minter_allowance_pointer(id).get_value::<u128>()
+    }
+
+    /// Get the pointer to the amount a minter has minted
+    fn minter_used_pointer(&self, id: &AlkaneId) -> StoragePointer {
+        let bytes: Vec<u8> = id.clone().into();
+        StoragePointer::from_keyword("/minters/used/").select(&bytes)
+    }
+
+    /// Get the amount a minter has minted
+    fn minter_used(&self, id: &AlkaneId) -> u128 {
+        self.minter_used_pointer(id).get_value::<u128>()
+    }
+
+    /// Get what is left of a minter's allowance
+    fn minter_remaining(&self, id: &AlkaneId) -> u128 {
+        self.minter_allowance(id).saturating_sub(self.minter_used(id))
+    }
+
+    /// List a minter with a lifetime allowance, or revoke it with 0
+    fn set_minter_allowance(&self, id: &AlkaneId, allowance: u128) -> Result<()> {
+        let mut pointer = self.minter_allowance_pointer(id);
+        if pointer.get().len() == 0 {
+            if allowance == 0 {
+                return Err(anyhow!("{}:{} is not a minter", id.block, id.tx));
+            }
+            if self.minter_ids_pointer().length() >= MAX_MINTERS {
+                return Err(anyhow!("at most {} minters are supported", MAX_MINTERS));
+            }
+            let bytes: Vec<u8> = id.clone().into();
+            self.minter_ids_pointer().append(Arc::new(bytes));
+        }
+        pointer.set_value::<u128>(allowance);
+        Ok(())
+    }
+
+    /// Charge a mint to a minter's allowance
+    fn use_minter_allowance(&self, id: &AlkaneId, amount: u128) -> Result<()> {
+        if self.minter_allowance(id) == 0 {
+            return Err(anyhow!("caller {}:{} is not an approved minter", id.block, id.tx));
+        }
+        if amount == 0 {
+            return Err(anyhow!("mint amount must be positive"));
+        }
+        if amount > self.minter_remaining(id) {
+            return Err(anyhow!(
+                "Minter allowance exceeded: {} requested, {} left",
+                amount,
+                self.minter_remaining(id)
+            ));
+        }
+        let mut pointer = self.minter_used_pointer(id);
+        let used = overflow_error(pointer.get_value::<u128>().checked_add(amount))
+            .map_err(|_| anyhow!("minter usage overflow"))?;
+        pointer.set_value::<u128>(used);
+        Ok(())
+    }
+}
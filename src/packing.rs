@@ -0,0 +1,57 @@
+//! Packing of strings into u128 opcode inputs
+//!
+//! Strings travel as little-endian u128 words: byte 0 of the string is the
+//! low byte of the first word. A zero byte terminates the string, so the
+//! padding after the last byte of a word list is never part of it, and
+//! strings themselves cannot contain NUL.
+
+use anyhow::{anyhow, Result};
+
+/// Bytes held by one word
+pub const WORD_BYTES: usize = 16;
+
+/// Pack a string into words, zero padding the last one
+pub fn encode(s: &str) -> Result<Vec<u128>> {
+    if s.as_bytes().contains(&0) {
+        return Err(anyhow!("string contains a NUL byte"));
+    }
+    Ok(s.as_bytes()
+        .chunks(WORD_BYTES)
+        .map(|chunk| {
+            let mut word = [0u8; WORD_BYTES];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u128::from_le_bytes(word)
+        })
+        .collect())
+}
+
+/// Pack a string of at most 16 bytes into a single word
+pub fn encode_word(s: &str) -> Result<u128> {
+    if s.len() > WORD_BYTES {
+        return Err(anyhow!(
+            "string of {} bytes does not fit a {}-byte word",
+            s.len(),
+            WORD_BYTES
+        ));
+    }
+    Ok(encode(s)?.first().cloned().unwrap_or(0))
+}
+
+/// Bytes of a word list up to the first terminator
+pub fn decode_bytes(words: &[u128]) -> Vec<u8> {
+    words
+        .iter()
+        .flat_map(|w| w.to_le_bytes())
+        .take_while(|b| *b != 0)
+        .collect()
+}
+
+/// Unpack a word list, stopping at the first terminator
+pub fn decode(words: &[u128]) -> Result<String> {
+    String::from_utf8(decode_bytes(words)).map_err(|_| anyhow!("string is not valid UTF-8"))
+}
+
+/// Unpack a single word, stopping at the first terminator
+pub fn decode_word(word: u128) -> Result<String> {
+    decode(&[word])
+}
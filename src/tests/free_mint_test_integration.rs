@@ -1,5 +1,10 @@
+use crate::ledger::LedgerEntry;
 use crate::tests::std::free_mint_build;
-use crate::{MintableAlkane, MintableToken, TokenName, ALKANE_FACTORY_FREE_MINT_ID};
+use crate::{
+    MintableAlkane, MintableToken, TokenName, ALKANE_FACTORY_FREE_MINT_ID,
+    ALKANE_FACTORY_OWNED_TOKEN_ID,
+};
+use alkanes::tests::std::alkanes_std_auth_token_build;
 use alkanes::indexer::index_block;
 use alkanes::message::AlkaneMessageContext;
 use alkanes::tests::helpers::{self as alkane_helpers, clear};
@@ -24,8 +29,11 @@ use wasm_bindgen_test::wasm_bindgen_test;
 
 // Helper function to create a block with a free-mint deployment
 fn init_block_with_free_mint_deployment() -> Result<(bitcoin::Block, AlkaneId)> {
-    let block_height = 840_000;
+    init_block_with_free_mint_options(vec![])
+}
 
+// Helper function to create a free-mint deployment with optional trailing Initialize inputs
+fn init_block_with_free_mint_options(options: Vec<u128>) -> Result<(bitcoin::Block, AlkaneId)> {
     // Initialize the free-mint contract
     let token_units = 1000u128;
     let value_per_mint = 10u128;
@@ -34,42 +42,40 @@ fn init_block_with_free_mint_deployment() -> Result<(bitcoin::Block, AlkaneId)>
     let name_part2 = 0x32u128; // "2" in little-endian
     let symbol = 0x545354u128; // "TST" in little-endian
 
-    let test_block = create_init_tx(
+    let mut inputs = vec![
+        0,
         token_units,
         value_per_mint,
         cap,
         name_part1,
         name_part2,
         symbol,
-    );
+    ];
+    inputs.extend(options);
+    let test_block = create_init_tx(inputs);
 
     Ok((test_block, AlkaneId::new(4, ALKANE_FACTORY_FREE_MINT_ID)))
 }
 
 // Helper function to create a transaction that initializes the free-mint contract
-fn create_init_tx(
-    token_units: u128,
-    value_per_mint: u128,
-    cap: u128,
-    name_part1: u128,
-    name_part2: u128,
-    symbol: u128,
-) -> bitcoin::Block {
+fn create_init_tx(inputs: Vec<u128>) -> bitcoin::Block {
     alkane_helpers::init_with_multiple_cellpacks_with_tx(
-        vec![free_mint_build::get_bytes()],
-        vec![Cellpack {
-            target: AlkaneId::new(3, ALKANE_FACTORY_FREE_MINT_ID),
+        vec![
+            alkanes_std_auth_token_build::get_bytes(),
+            free_mint_build::get_bytes(),
+        ],
+        vec![
+            // Deploy the auth token template used for owner access
+            Cellpack {
+                target: AlkaneId::new(3, ALKANE_FACTORY_OWNED_TOKEN_ID),
+                inputs: vec![100],
+            },
             // Initialize opcode (0) with parameters
-            inputs: vec![
-                0,
-                token_units,
-                value_per_mint,
-                cap,
-                name_part1,
-                name_part2,
-                symbol,
-            ],
-        }],
+            Cellpack {
+                target: AlkaneId::new(3, ALKANE_FACTORY_FREE_MINT_ID),
+                inputs,
+            },
+        ],
     )
 }
 
@@ -149,3 +155,86 @@ fn test_free_mint_initialization() -> Result<()> {
     );
     Ok(())
 }
+
+#[wasm_bindgen_test]
+fn test_free_mint_default_decimals() -> Result<()> {
+    clear();
+
+    let block_height = 840_000;
+    let (test_block, free_mint_deployment) = init_block_with_free_mint_deployment()?;
+    index_block(&test_block, block_height)?;
+
+    // GetDecimals (114) falls back to the default when none was given
+    let decimals = view::call_view(&free_mint_deployment, &vec![114], 100_000)?;
+    assert_eq!(decimals, vec![8u8], "Decimals should default to 8");
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_custom_decimals() -> Result<()> {
+    clear();
+
+    let block_height = 840_000;
+    // Default admin count, threshold and timelock delay, then 2 decimals
+    let (test_block, free_mint_deployment) = init_block_with_free_mint_options(vec![0, 0, 0, 2])?;
+    index_block(&test_block, block_height)?;
+
+    let decimals = view::call_view(&free_mint_deployment, &vec![114], 100_000)?;
+    assert_eq!(decimals, vec![2u8], "Decimals should match the Initialize input");
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_zero_decimals() -> Result<()> {
+    clear();
+
+    let block_height = 840_000;
+    // An explicit 0 is kept, only a missing input falls back to the default
+    let (test_block, free_mint_deployment) = init_block_with_free_mint_options(vec![0, 0, 0, 0])?;
+    index_block(&test_block, block_height)?;
+
+    let decimals = view::call_view(&free_mint_deployment, &vec![114], 100_000)?;
+    assert_eq!(decimals, vec![0u8], "An explicit 0 decimals input should be kept");
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_premine_in_ledger() -> Result<()> {
+    clear();
+
+    let block_height = 840_000;
+    let (test_block, free_mint_deployment) = init_block_with_free_mint_deployment()?;
+    index_block(&test_block, block_height)?;
+
+    // GetMintCount (119) counts the premine as the first mint
+    let count = view::call_view(&free_mint_deployment, &vec![119], 100_000)?;
+    assert_eq!(count, 1u128.to_le_bytes().to_vec(), "The premine should be in the ledger");
+
+    // GetMintRange (118) returns it with the deployment txid and token_units
+    let range = view::call_view(&free_mint_deployment, &vec![118, 0, 10], 100_000)?;
+    let entries = LedgerEntry::decode_range(&range)?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].height, block_height as u64);
+    assert!(test_block.txdata.iter().any(|tx| tx.compute_txid() == entries[0].txid));
+    assert_eq!(entries[0].amount, 1000u128);
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_free_mint_draft_input() -> Result<()> {
+    clear();
+
+    let block_height = 840_000;
+    // Options up to decimals, no upload or word lists, then the draft flag
+    let mut options = vec![0, 0, 0, 8, 0, 0, 0, 0];
+    options.extend([0, 0, 0, 0, 0, 1]);
+    let (test_block, free_mint_deployment) = init_block_with_free_mint_options(options)?;
+    index_block(&test_block, block_height)?;
+
+    // GetState (125) reports Draft, and views keep working in it
+    let state = view::call_view(&free_mint_deployment, &vec![125], 100_000)?;
+    assert_eq!(state[0], 0u8, "The draft input should start the token in Draft");
+    let decimals = view::call_view(&free_mint_deployment, &vec![114], 100_000)?;
+    assert_eq!(decimals, vec![8u8]);
+    Ok(())
+}
@@ -1,9 +1,38 @@
 use std::sync::Arc;
 
-use crate::{MintableAlkane, MintableToken, TokenName};
+use crate::admin::{proposal_id, MultisigAdmin};
+use crate::airdrop::{airdrop_leaf, hash_pair, Airdrop};
+use crate::checkpoints::CheckpointList;
+use crate::config::{
+    config_payload, is_config, InitConfig, CONFIG_MAGIC, TAG_DECIMALS, TAG_OPTIONAL,
+};
+use crate::data::{
+    content_type_from_words, envelope_payload, sniff_content_type, DataCommitment, DataInfo, DataReference,
+    DataUpload,
+};
+use crate::events::{decode_events, encode_events, Event};
+use crate::ledger::{
+    minter_recipient_hash, recipient_script, txid_from_parts, txid_to_parts, LedgerEntry, MintRecord,
+};
+use crate::lifecycle::{LaunchState, Lifecycle, ANY_STATE, OPEN_STATES, VIEW_STATES};
+use crate::metadata::{decode_keys, encode_keys, Metadata};
+use crate::minters::Minters;
+use crate::packing;
+use crate::timelock::{ChangeStatus, Timelock, ACTION_SET_CAP, ACTION_SET_VALUE_PER_MINT};
+use crate::token_info::TokenInfo;
+use crate::treasury::Treasury;
+use crate::{trim, MintableAlkane, MintableToken, TokenName};
 use alkanes_runtime::storage::StoragePointer;
+use alkanes_support::context::Context;
+use alkanes_support::gz;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::parcel::{AlkaneTransfer, AlkaneTransferParcel};
 use anyhow::Result;
+use bitcoin::hashes::Hash;
+use bitcoin::Txid;
 use metashrew_support::index_pointer::KeyValuePointer;
+use ordinals::Runestone;
+use protorune_support::protostone::{Protostone, Protostones};
 use wasm_bindgen_test::wasm_bindgen_test;
 
 // Reset all storage keys used in tests
@@ -18,6 +47,51 @@ fn reset_test_storage() {
     StoragePointer::from_keyword("/cap").set(Arc::new(Vec::new()));
     StoragePointer::from_keyword("/data").set(Arc::new(Vec::new()));
     StoragePointer::from_keyword("/tx-hashes").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-info").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-compressed").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-plain").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-upload").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-ref").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-input").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-max-len").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/state").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-hashes/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/meta/keys/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/meta/frozen").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/admins/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/admin-threshold").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/timelock/delay").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/timelock/queue/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/mints/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/checkpoints/supply/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/checkpoints/minted/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/airdrop/root").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/airdrop/expiry").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/airdrop/remaining").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/burned").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/decimals").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/treasury/ids/length").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/treasury/balances/")
+        .select(&AlkaneId::new(2, 0).into())
+        .set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/finalization").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/minters/ids/length").set(Arc::new(Vec::new()));
+    for prefix in ["/minters/allowances/", "/minters/used/"] {
+        StoragePointer::from_keyword(prefix)
+            .select(&AlkaneId::new(2, 7).into())
+            .set(Arc::new(Vec::new()));
+    }
+}
+
+// Build a context carrying the given inputs and incoming alkanes
+fn context_with(inputs: Vec<u128>, incoming: Vec<AlkaneTransfer>) -> Context {
+    Context {
+        inputs,
+        myself: AlkaneId::new(2, 1),
+        caller: AlkaneId::default(),
+        incoming_alkanes: AlkaneTransferParcel(incoming),
+        vout: 0,
+    }
 }
 
 #[wasm_bindgen_test]
@@ -87,7 +161,7 @@ fn test_name_and_symbol() -> Result<()> {
     alkane.observe_initialization()?;
 
     // Set name and symbol directly using the MintableToken trait methods
-    // Note: We need to use little-endian encoding because of how trim() works
+    // Note: We need to use little-endian encoding, see the `packing` module
     let name_part1 = 0x54534554u128; // "TEST" in little-endian
     let name_part2 = 0x32u128; // "2" in little-endian
     let symbol = 0x545354u128; // "TST" in little-endian
@@ -146,3 +220,1240 @@ fn test_total_supply_increase() -> Result<()> {
 
     Ok(())
 }
+
+#[wasm_bindgen_test]
+fn test_finalization_freezes_cap() {
+    // Reset storage
+    reset_test_storage();
+
+    // Create the MintableAlkane instance
+    let alkane = MintableAlkane::default();
+    alkane.set_cap(100u128);
+    alkane.set_minted(3u128);
+    assert!(!alkane.is_finalized());
+    assert!(alkane.finalization().is_none());
+
+    // Finalize at a known height and txid
+    let txid = Txid::from_slice(&[7u8; 32]).unwrap();
+    alkane.set_finalization(840_000, &txid);
+
+    // The cap is frozen at the mint count and the record is kept
+    assert!(alkane.is_finalized());
+    assert_eq!(alkane.cap(), 3u128);
+    assert_eq!(alkane.finalization(), Some((840_000u64, txid)));
+}
+
+#[wasm_bindgen_test]
+fn test_finalization_with_no_mints_keeps_zero_cap() {
+    // Reset storage
+    reset_test_storage();
+
+    // Create the MintableAlkane instance
+    let alkane = MintableAlkane::default();
+    alkane.set_cap(0u128);
+
+    // Finalizing before any mint must not fall back to an unlimited cap
+    alkane.set_finalization(1, &Txid::all_zeros());
+    assert_eq!(alkane.cap(), 0u128);
+}
+
+#[wasm_bindgen_test]
+fn test_multisig_requires_threshold_in_one_call() {
+    // Reset storage
+    reset_test_storage();
+
+    // Register three admins with a threshold of two
+    let alkane = MintableAlkane::default();
+    let admins = [AlkaneId::new(2, 10), AlkaneId::new(2, 11), AlkaneId::new(2, 12)];
+    for admin in admins.iter() {
+        alkane.add_admin_token(admin);
+    }
+    alkane.set_admin_threshold(2);
+    assert_eq!(alkane.admin_tokens(), admins.to_vec());
+
+    // One admin token is not enough
+    let one = context_with(vec![78], vec![AlkaneTransfer { id: admins[0], value: 1 }]);
+    assert!(alkane.only_owner(&one).is_err());
+
+    // Two distinct admin tokens in the same call are
+    let two = context_with(
+        vec![78],
+        vec![
+            AlkaneTransfer { id: admins[0], value: 1 },
+            AlkaneTransfer { id: admins[2], value: 1 },
+        ],
+    );
+    assert!(alkane.only_owner(&two).is_ok());
+}
+
+#[wasm_bindgen_test]
+fn test_multisig_approvals_across_transactions() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    // Register three admins with a threshold of two
+    let alkane = MintableAlkane::default();
+    let admins = [AlkaneId::new(2, 10), AlkaneId::new(2, 11), AlkaneId::new(2, 12)];
+    for admin in admins.iter() {
+        alkane.add_admin_token(admin);
+    }
+    alkane.set_admin_threshold(2);
+
+    // Admin 1 approves the Finalize call in an earlier transaction
+    let id = proposal_id(&[78]);
+    alkane.record_approval(id, 1)?;
+    alkane.record_approval(id, 1)?;
+    assert_eq!(alkane.approvals(id), vec![1u32]);
+
+    // Admin 0 then executes it alone
+    let call = context_with(vec![78], vec![AlkaneTransfer { id: admins[0], value: 1 }]);
+    assert!(alkane.only_owner(&call).is_ok());
+
+    // The approval was consumed and cannot be replayed
+    assert!(alkane.approvals(id).is_empty());
+    assert!(alkane.only_owner(&call).is_err());
+
+    // Approvals for a different call do not count
+    alkane.record_approval(proposal_id(&[79, 1]), 1)?;
+    assert!(alkane.only_owner(&call).is_err());
+
+    // A call executed directly by enough admins spends earlier approvals too
+    let withdraw = proposal_id(&[79, 1]);
+    let both = context_with(
+        vec![79, 1],
+        vec![
+            AlkaneTransfer { id: admins[0], value: 1 },
+            AlkaneTransfer { id: admins[1], value: 1 },
+        ],
+    );
+    assert!(alkane.only_owner(&both).is_ok());
+    assert!(alkane.approvals(withdraw).is_empty());
+
+    // So a single admin cannot replay it with the stale approval
+    let replay = context_with(vec![79, 1], vec![AlkaneTransfer { id: admins[2], value: 1 }]);
+    assert!(alkane.only_owner(&replay).is_err());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_timelock_queue_execute_cancel() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    // Queue two changes with a ten block delay
+    let alkane = MintableAlkane::default();
+    alkane.set_timelock_delay(10)?;
+    let first = alkane.enqueue_change(ACTION_SET_VALUE_PER_MINT, 25, 100)?;
+    let second = alkane.enqueue_change(ACTION_SET_CAP, 500, 100)?;
+    assert_eq!(alkane.queued_change(first)?.eta, 110);
+    assert_eq!(alkane.pending_changes(), vec![first, second]);
+
+    // Execution is refused before the eta and allowed from it
+    assert!(alkane.take_executable_change(first, 109).is_err());
+    let change = alkane.take_executable_change(first, 110)?;
+    assert_eq!(change.value, 25);
+    assert_eq!(alkane.queued_change(first)?.status, ChangeStatus::Executed);
+    assert!(alkane.take_executable_change(first, 200).is_err());
+
+    // A cancelled change can no longer be executed
+    alkane.close_change(second, ChangeStatus::Cancelled)?;
+    assert!(alkane.take_executable_change(second, 200).is_err());
+    assert!(alkane.pending_changes().is_empty());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_timelock_delay_only_increases() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    alkane.set_timelock_delay(10)?;
+    assert!(alkane.set_timelock_delay(9).is_err());
+    alkane.set_timelock_delay(20)?;
+    assert_eq!(alkane.timelock_delay(), 20);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_treasury_credit_and_debit() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let payment = AlkaneId::new(2, 0);
+    alkane.credit_treasury(&payment, 40)?;
+    alkane.credit_treasury(&payment, 2)?;
+    assert_eq!(alkane.treasury_balance(&payment), 42);
+    assert_eq!(alkane.treasury_ids(), vec![payment]);
+
+    // Debits cannot exceed the recorded balance
+    assert!(alkane.debit_treasury(&payment, 43).is_err());
+    alkane.debit_treasury(&payment, 42)?;
+    assert_eq!(alkane.treasury_balance(&payment), 0);
+
+    // Crediting again does not list the id twice
+    alkane.credit_treasury(&payment, 1)?;
+    assert_eq!(alkane.treasury_ids().len(), 1);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_burn_reduces_total_supply() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    alkane.increase_total_supply(100u128)?;
+
+    // Burning lowers the supply and tracks the running total
+    alkane.record_burn(30u128)?;
+    alkane.record_burn(20u128)?;
+    assert_eq!(alkane.total_supply(), 50u128);
+    assert_eq!(alkane.burned(), 50u128);
+
+    // More than the supply cannot be burned
+    assert!(alkane.record_burn(51u128).is_err());
+    assert_eq!(alkane.total_supply(), 50u128);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_decimals_default_and_zero() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    assert_eq!(alkane.decimals(), 8u8);
+
+    // An explicit zero is kept rather than replaced by the default
+    alkane.set_decimals(0)?;
+    assert_eq!(alkane.decimals(), 0u8);
+    assert!(alkane.set_decimals(39).is_err());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_token_info_round_trip() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    alkane.set_value_per_mint(10u128);
+    alkane.set_cap(100u128);
+    alkane.set_minted(4u128);
+    alkane.increase_total_supply(1040u128)?;
+    <MintableAlkane as MintableToken>::set_name_and_symbol(
+        &alkane,
+        TokenName::new(0x54534554u128, 0x32u128),
+        0x545354u128,
+    );
+
+    // The bundled response decodes back to the stored state
+    let info = TokenInfo::decode(&alkane.token_info(0).encode())?;
+    assert_eq!(info.name, "TEST2");
+    assert_eq!(info.symbol, "TST");
+    assert_eq!(info.total_supply, 1040u128);
+    assert_eq!(info.cap, 100u128);
+    assert_eq!(info.minted, 4u128);
+    assert_eq!(info.value_per_mint, 10u128);
+    assert_eq!(info.decimals, 8u8);
+    assert!(!info.finalized);
+
+    // The JSON form carries the same values
+    let json = alkane.token_info(0).to_json();
+    assert!(json.contains("\"name\":\"TEST2\""));
+    assert!(json.contains("\"total_supply\":\"1040\""));
+
+    // Truncated input is rejected
+    assert!(TokenInfo::decode(&alkane.token_info(0).encode()[..10]).is_err());
+
+    // Version 1 responses, which end at data_len, still decode
+    let mut v1 = alkane.token_info(0).encode();
+    v1.truncate(v1.len() - 4);
+    v1[0] = 1;
+    assert_eq!(TokenInfo::decode(&v1)?, info);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_mint_record_by_txid() {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let txid = Txid::from_slice(&[9u8; 32]).unwrap();
+    assert!(!alkane.has_tx_hash(&txid));
+    assert!(alkane.mint_record(&txid).is_none());
+
+    // A stored record marks the txid as used and can be read back
+    let record = MintRecord {
+        index: 3,
+        height: 840_001,
+        amount: 10,
+    };
+    alkane.set_mint_record(&txid, &record);
+    assert!(alkane.has_tx_hash(&txid));
+    assert_eq!(alkane.mint_record(&txid), Some(record));
+
+    // The opcode input form addresses the same txid
+    let (hi, lo) = txid_to_parts(&txid);
+    assert_eq!(txid_from_parts(hi, lo), txid);
+}
+
+#[wasm_bindgen_test]
+fn test_mint_ledger_range() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let entries: Vec<LedgerEntry> = (0..5u8)
+        .map(|i| LedgerEntry {
+            height: 840_000 + i as u64,
+            txid: Txid::from_slice(&[i; 32]).unwrap(),
+            amount: 10,
+            recipient_script_hash: [i; 32],
+        })
+        .collect();
+    for entry in entries.iter() {
+        alkane.append_mint_ledger(entry);
+    }
+    assert_eq!(alkane.mint_ledger_count(), 5);
+
+    // A page in the middle decodes to the stored entries
+    let page = LedgerEntry::decode_range(&alkane.mint_ledger_range(1, 2))?;
+    assert_eq!(page, entries[1..3].to_vec());
+
+    // Pages are clipped to the ledger length
+    assert_eq!(LedgerEntry::decode_range(&alkane.mint_ledger_range(3, 10))?.len(), 2);
+    assert!(alkane.mint_ledger_range(7, 2).is_empty());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_event_block_round_trip() -> Result<()> {
+    let events = vec![
+        Event::Minted {
+            index: 99,
+            amount: 10,
+        },
+        Event::Finalized {
+            height: 840_000,
+            minted: 100,
+        },
+        Event::Deposited {
+            id: AlkaneId::new(2, 0),
+            amount: 5,
+        },
+    ];
+
+    // Events appended after opcode data are split back out
+    let mut data = 7u128.to_le_bytes().to_vec();
+    data.extend(encode_events(&events));
+    let (own, decoded) = decode_events(&data)?;
+    assert_eq!(own, &7u128.to_le_bytes()[..]);
+    assert_eq!(decoded, events);
+
+    // Responses without an event block have no events
+    let (own, decoded) = decode_events(&[1, 2, 3])?;
+    assert_eq!(own, &[1u8, 2, 3][..]);
+    assert!(decoded.is_empty());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_checkpoint_lookup_by_height() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let checkpoints = CheckpointList::from_keyword("/checkpoints/supply");
+    checkpoints.record(100, 1000)?;
+    checkpoints.record(105, 1010)?;
+    // A second change in the same block replaces that block's checkpoint
+    checkpoints.record(105, 1020)?;
+    checkpoints.record(120, 900)?;
+    assert_eq!(checkpoints.len(), 3);
+
+    assert_eq!(checkpoints.value_at(99)?, 0);
+    assert_eq!(checkpoints.value_at(100)?, 1000);
+    assert_eq!(checkpoints.value_at(104)?, 1000);
+    assert_eq!(checkpoints.value_at(105)?, 1020);
+    assert_eq!(checkpoints.value_at(119)?, 1020);
+    assert_eq!(checkpoints.value_at(u64::MAX)?, 900);
+
+    // Checkpoints cannot be written out of height order
+    assert!(checkpoints.record(110, 1).is_err());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_airdrop_claim_and_reclaim() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    // Two-leaf tree: the proof of each leaf is the other leaf
+    let alice = vec![0x51u8, 0x20, 0xaa];
+    let bob = vec![0x51u8, 0x20, 0xbb];
+    let alice_leaf = airdrop_leaf(&alice, 60);
+    let bob_leaf = airdrop_leaf(&bob, 40);
+    let root = hash_pair(&alice_leaf, &bob_leaf);
+
+    // A leaf whose preimage is the children of the root does not hash to it
+    let (low, high) = if alice_leaf <= bob_leaf {
+        (alice_leaf, bob_leaf)
+    } else {
+        (bob_leaf, alice_leaf)
+    };
+    let mut script = low.to_vec();
+    script.extend_from_slice(&high[..16]);
+    let amount = u128::from_le_bytes(high[16..].try_into()?);
+    assert_ne!(airdrop_leaf(&script, amount), root);
+
+    let alkane = MintableAlkane::default();
+    alkane.open_airdrop(root, 900, 100)?;
+    assert!(alkane.open_airdrop(root, 900, 100).is_err());
+
+    // A wrong amount or proof is rejected
+    assert!(alkane.claim_airdrop_leaf(&alice, 61, &bob_leaf, 800).is_err());
+    assert!(alkane.claim_airdrop_leaf(&alice, 60, &alice_leaf, 800).is_err());
+
+    // A valid claim pays once
+    assert_eq!(alkane.claim_airdrop_leaf(&alice, 60, &bob_leaf, 800)?, 60);
+    assert!(alkane.claim_airdrop_leaf(&alice, 60, &bob_leaf, 800).is_err());
+    assert_eq!(alkane.airdrop_remaining(), 40);
+
+    // Claims stop and reclaiming opens after expiry
+    assert!(alkane.reclaim_airdrop_pool(900).is_err());
+    assert!(alkane.claim_airdrop_leaf(&bob, 40, &alice_leaf, 901).is_err());
+    assert_eq!(alkane.reclaim_airdrop_pool(901)?, 40);
+    assert_eq!(alkane.airdrop_remaining(), 0);
+
+    // The emptied pool can be replaced, and claims of the old root do not carry over
+    let carol = vec![0x51u8, 0x20, 0xcc];
+    let carol_leaf = airdrop_leaf(&carol, 60);
+    let next_root = hash_pair(&alice_leaf, &carol_leaf);
+    alkane.open_airdrop(next_root, 1900, 120)?;
+    assert!(alkane.open_airdrop(root, 1900, 120).is_err());
+    assert_eq!(alkane.claim_airdrop_leaf(&alice, 60, &carol_leaf, 1000)?, 60);
+
+    // A contract calling on behalf of the transaction cannot claim
+    alkane.store_state(LaunchState::Live, 0);
+    let mut nested = context_with(vec![87, 60], vec![]);
+    nested.caller = AlkaneId::new(2, 9);
+    let err = alkane.claim_airdrop_with(&nested, 60, 1000).err().unwrap();
+    assert_eq!(err.to_string(), "ClaimAirdrop must be called directly by the transaction");
+    assert_eq!(alkane.airdrop_remaining(), 60);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_data_info_content_type() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    assert_eq!(sniff_content_type(b"\x89PNG\r\n\x1a\n...."), "image/png");
+    assert_eq!(sniff_content_type(b"  <svg xmlns='http://www.w3.org/2000/svg'/>"), "image/svg+xml");
+    assert_eq!(sniff_content_type(b"<!DOCTYPE html><html></html>"), "text/html");
+    assert_eq!(sniff_content_type(b"{\"a\":1}"), "application/json");
+    assert_eq!(sniff_content_type(&[0x00, 0xff, 0xfe]), "application/octet-stream");
+
+    // An explicit content type from the Initialize input wins over sniffing
+    let alkane = MintableAlkane::default();
+    alkane.store_data(b"{\"a\":1}".to_vec())?;
+    let packed = u128::from_le_bytes(*b"text/plain\0\0\0\0\0\0");
+    let info = alkane.record_data_info(Some(content_type_from_words(&[packed])?))?;
+    assert_eq!(info.content_type, "text/plain");
+
+    // Types longer than one word span a word list
+    let words = packing::encode("application/octet-stream")?;
+    assert_eq!(words.len(), 2);
+    assert_eq!(content_type_from_words(&words)?, "application/octet-stream");
+    assert!(content_type_from_words(&packing::encode("text/ plain")?).is_err());
+    assert_eq!(info.encoding, "identity");
+    assert_eq!(info.stored_len, 7);
+    assert_eq!(info.plain_len, 7);
+    assert_eq!(DataInfo::decode(&info.encode())?, info);
+
+    let info = alkane.record_data_info(None)?;
+    assert_eq!(info.content_type, "application/json");
+    assert_eq!(alkane.data_info()?, info);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_uncompressed_data() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let plain = b"<svg xmlns='http://www.w3.org/2000/svg'/>".to_vec();
+    alkane.store_data(plain.clone())?;
+
+    // Uncompressed data is served as-is rather than as an empty blob
+    assert!(!alkane.data_is_compressed());
+    assert_eq!(alkane.data(), plain);
+    assert_eq!(alkane.raw_data(), plain);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_compressed_data() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let plain = b"hello hello hello hello".to_vec();
+    let compressed = gz::compress(plain.clone())?;
+    alkane.store_data(compressed.clone())?;
+
+    assert!(alkane.data_is_compressed());
+    assert_eq!(alkane.data(), plain);
+    assert_eq!(alkane.raw_data(), compressed);
+
+    // Token info reports the decompressed length, like GetDataLength
+    assert_eq!(alkane.token_info(0).data_len, plain.len() as u64);
+
+    // Deployments without the flag or cache fall back to the gzip magic bytes
+    StoragePointer::from_keyword("/data-compressed").set(Arc::new(Vec::new()));
+    StoragePointer::from_keyword("/data-plain").set(Arc::new(Vec::new()));
+    assert!(alkane.data_is_compressed());
+    assert_eq!(alkane.data(), plain);
+
+    // Raw data that only starts with the gzip magic is stored and served as is
+    let raw = vec![0x1f, 0x8b, b'n', b'o', b't', b' ', b'g', b'z'];
+    alkane.store_data(raw.clone())?;
+    assert!(!alkane.data_is_compressed());
+    assert_eq!(alkane.data(), raw);
+    assert_eq!(alkane.raw_data(), raw);
+    assert_eq!(alkane.data_len(), raw.len() as u128);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_data_range() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let plain: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    alkane.store_data(gz::compress(plain.clone())?)?;
+
+    assert_eq!(alkane.data_len(), 1000);
+    assert_eq!(alkane.data_range(0, 10), plain[0..10].to_vec());
+    assert_eq!(alkane.data_range(995, 10), plain[995..].to_vec());
+    assert!(alkane.data_range(1000, 10).is_empty());
+    assert!(alkane.data_range(u128::MAX, u128::MAX).is_empty());
+
+    // Reassembling the chunks gives back the whole blob
+    let chunks: Vec<u8> = (0..1000u128)
+        .step_by(128)
+        .flat_map(|offset| alkane.data_range(offset, 128))
+        .collect();
+    assert_eq!(chunks, plain);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_multi_transaction_data_upload() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let plain: Vec<u8> = (0..=255u8).cycle().take(3000).collect();
+    let segment = gz::compress(plain.clone())?;
+    let (first, rest) = segment.split_at(segment.len() / 2);
+    let upload = DataUpload {
+        size: segment.len() as u64,
+        sha256: bitcoin::hashes::sha256::Hash::hash(&segment).to_byte_array(),
+        content_type: String::from("application/octet-stream"),
+    };
+
+    // The Initialize witness carries the first chunk
+    alkane.begin_data_upload(upload.clone(), first.to_vec())?;
+    assert_eq!(alkane.data_upload()?, Some(upload.clone()));
+    assert!(alkane.require_data_sealed().is_err());
+
+    // Sealing an incomplete segment fails
+    let txid = Txid::from_byte_array([5u8; 32]);
+    assert!(alkane.seal_data_upload(&txid, 840_000).is_err());
+
+    // Chunks past the declared size are refused
+    let mut oversized = rest.to_vec();
+    oversized.push(0);
+    assert!(alkane.append_data_chunk(&oversized).is_err());
+
+    assert_eq!(alkane.append_data_chunk(rest)?, segment.len() as u64);
+    assert_eq!(alkane.seal_data_upload(&txid, 840_000)?, segment.len() as u64);
+
+    // Once sealed the segment is served and can't be extended
+    alkane.require_data_sealed()?;
+    assert_eq!(alkane.data(), plain);
+    assert_eq!(alkane.data_info()?.content_type, "application/octet-stream");
+
+    // Sealing commits the declared hash, which DataInfo reports as well
+    let commitment = alkane.data_commitment().unwrap();
+    assert_eq!(commitment.sha256, upload.sha256);
+    assert_eq!(commitment.txid, txid);
+    assert_eq!(alkane.data_info()?.sha256, upload.sha256);
+    assert!(alkane.append_data_chunk(b"more").is_err());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_data_upload_hash_mismatch() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let upload = DataUpload {
+        size: 4,
+        sha256: bitcoin::hashes::sha256::Hash::hash(b"abcd").to_byte_array(),
+        content_type: String::new(),
+    };
+    alkane.begin_data_upload(upload, b"ab".to_vec())?;
+    alkane.append_data_chunk(b"cx")?;
+
+    let txid = Txid::from_byte_array([6u8; 32]);
+    assert!(alkane.seal_data_upload(&txid, 840_000).is_err());
+    assert!(alkane.require_data_sealed().is_err());
+    assert_eq!(alkane.data_commitment(), None);
+
+    // Empty chunks are refused rather than accepted as no-ops
+    assert!(alkane.append_data_chunk(b"").is_err());
+
+    // Aborting drops the chunks so the segment can be uploaded again
+    alkane.discard_data_chunks()?;
+    assert!(alkane.raw_data().is_empty());
+    alkane.append_data_chunk(b"abcd")?;
+    assert_eq!(alkane.seal_data_upload(&txid, 840_000)?, 4);
+    assert!(alkane.discard_data_chunks().is_err());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_data_hash_commitments() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    assert_eq!(alkane.data_commitment(), None);
+    assert!(alkane.data_hash_history().is_empty());
+
+    alkane.store_data(b"first".to_vec())?;
+    let txid = Txid::from_byte_array([7u8; 32]);
+    let first = alkane.commit_data_hash(&txid, 840_000);
+    assert_eq!(
+        first.sha256,
+        bitcoin::hashes::sha256::Hash::hash(b"first").to_byte_array()
+    );
+    assert_eq!(alkane.data_commitment(), Some(first));
+
+    // A later commitment becomes current and the earlier one stays in the history
+    alkane.store_data(b"second".to_vec())?;
+    let second = alkane.commit_data_hash(&Txid::from_byte_array([8u8; 32]), 840_010);
+    assert_eq!(alkane.data_commitment(), Some(second));
+    assert_eq!(
+        DataCommitment::decode_history(&alkane.data_hash_history())?,
+        vec![first, second]
+    );
+
+    // For gzip data the commitment and DataInfo both cover the stored bytes
+    let compressed = gz::compress(b"third third third".to_vec())?;
+    alkane.store_data(compressed.clone())?;
+    let third = alkane.commit_data_hash(&Txid::from_byte_array([9u8; 32]), 840_020);
+    let info = alkane.record_data_info(None)?;
+    assert_eq!(third.sha256, bitcoin::hashes::sha256::Hash::hash(&compressed).to_byte_array());
+    assert_eq!(info.sha256, third.sha256);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_long_name_and_symbol() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let name = "Extraordinarily Long Token Name — ünïcödé edition";
+    let words = packing::encode(name)?;
+    assert!(name.len() > 32);
+    assert_eq!(packing::decode(&words)?, name);
+
+    let alkane = MintableAlkane::default();
+    alkane.set_long_name_and_symbol(name, "LONGSYMBOL-EXTENDED");
+    assert_eq!(<MintableAlkane as MintableToken>::name(&alkane), name);
+    assert_eq!(
+        <MintableAlkane as MintableToken>::symbol(&alkane),
+        "LONGSYMBOL-EXTENDED"
+    );
+
+    Ok(())
+}
+
+/// Small xorshift generator so the packing properties run over many inputs
+/// without pulling in a property testing crate
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A random string of up to `max_chars` non-NUL chars, ASCII and multi-byte
+    fn string(&mut self, max_chars: u64) -> String {
+        const ALPHABET: &[char] = &[
+            'a', 'Z', '7', ' ', '-', '\u{7f}', 'é', 'ß', '€', '字', '🚀',
+        ];
+        let len = self.next() % (max_chars + 1);
+        (0..len)
+            .map(|_| ALPHABET[(self.next() % ALPHABET.len() as u64) as usize])
+            .collect()
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_packing_round_trip_property() -> Result<()> {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..1000 {
+        let s = rng.string(64);
+        let words = packing::encode(&s)?;
+        assert_eq!(words.len(), s.len().div_ceil(packing::WORD_BYTES));
+        assert_eq!(packing::decode(&words)?, s);
+        if s.len() <= packing::WORD_BYTES {
+            assert_eq!(packing::decode_word(packing::encode_word(&s)?)?, s);
+        } else {
+            assert!(packing::encode_word(&s).is_err());
+        }
+    }
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_packing_terminator_and_bad_input() -> Result<()> {
+    // Decoding stops at the first terminator, keeping bytes before it intact
+    let word = u128::from_le_bytes(*b"AB\0CD\0\0\0\0\0\0\0\0\0\0\0");
+    assert_eq!(packing::decode_word(word)?, "AB");
+    let full = packing::encode_word("0123456789abcdef")?;
+    assert_eq!(packing::decode(&[full, word])?, "0123456789abcdefAB");
+    assert_eq!(trim(word), "AB");
+
+    // Strings with NUL can't be packed and invalid UTF-8 doesn't unpack
+    assert!(packing::encode("a\0b").is_err());
+    assert!(packing::decode_word(0xff).is_err());
+    assert!(TokenName::new(0x54534554, 0xfe).decode().is_err());
+
+    // Name parts are decoded one by one, so a padded first part still joins the second
+    assert_eq!(TokenName::new(0x54534554, 0x32).decode()?, "TEST2");
+    assert_eq!(TokenName::new(word, word).decode()?, "ABAB");
+
+    // Invalid UTF-8 no longer panics the lossy helper
+    assert_eq!(trim(0xff), "\u{fffd}");
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_metadata_attributes() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let website = packing::encode_word("website")?;
+    let license = packing::encode_word("license")?;
+    let key = alkane.store_attribute(website, b"https://example.com".to_vec())?;
+    assert_eq!(key, "website");
+    alkane.store_attribute(license, b"CC0-1.0".to_vec())?;
+
+    // Overwriting keeps a single key in insertion order
+    alkane.store_attribute(website, b"https://example.org".to_vec())?;
+    assert_eq!(alkane.attribute("website"), b"https://example.org".to_vec());
+    assert_eq!(alkane.attribute_keys(), vec!["website", "license"]);
+    assert_eq!(
+        decode_keys(&encode_keys(&alkane.attribute_keys()))?,
+        alkane.attribute_keys()
+    );
+    assert!(alkane.attribute("socials").is_empty());
+    assert!(alkane.store_attribute(0, b"x".to_vec()).is_err());
+
+    // Attributes appear in the bundled token info
+    let info = TokenInfo::decode(&alkane.token_info(0).encode())?;
+    assert_eq!(info.attributes.len(), 2);
+    assert_eq!(info.attributes[1], (String::from("license"), b"CC0-1.0".to_vec()));
+    assert!(alkane
+        .token_info(0)
+        .to_json()
+        .contains("\"attributes\":{\"website\":\"https://example.org\",\"license\":\"CC0-1.0\"}"));
+
+    // Frozen attributes can't change
+    alkane.freeze_attribute_map()?;
+    assert!(alkane.store_attribute(license, b"MIT".to_vec()).is_err());
+    assert!(alkane.freeze_attribute_map().is_err());
+    assert!(TokenInfo::decode(&alkane.token_info(0).encode())?.attributes_frozen);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_external_data_reference() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let inscription = DataReference::Inscription {
+        txid: Txid::from_byte_array([9u8; 32]),
+        index: 3,
+    };
+    assert_eq!(DataReference::decode(&inscription.encode())?, inscription);
+    assert!(inscription.to_string().ends_with("i3"));
+
+    // Once set, data reads point at the reference instead of returning nothing
+    alkane.set_data_reference(&inscription)?;
+    assert_eq!(alkane.data_reference()?, Some(inscription.clone()));
+    let err = alkane.require_data_available().unwrap_err().to_string();
+    assert!(err.contains(&inscription.to_string()));
+
+    // The reference is fixed once set
+    let uri = DataReference::uri(b"ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3o".to_vec())?;
+    assert_eq!(DataReference::decode(&uri.encode())?, uri);
+    assert!(alkane.set_data_reference(&uri).is_err());
+
+    // Embedded data can't be swapped for a reference
+    reset_test_storage();
+    alkane.store_data(b"embedded".to_vec())?;
+    assert!(alkane.set_data_reference(&uri).is_err());
+    alkane.require_data_available()?;
+
+    // URIs need a scheme and no whitespace
+    assert!(DataReference::uri(b"example.com/art.png".to_vec()).is_err());
+    assert!(DataReference::uri(b"https://example.com/my art.png".to_vec()).is_err());
+    assert!(DataReference::uri(Vec::new()).is_err());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_data_envelope_options() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    // Without options every input is scanned and any size is accepted
+    let alkane = MintableAlkane::default();
+    assert_eq!(alkane.data_input(), None);
+    alkane.check_data_len(u64::MAX)?;
+
+    alkane.set_data_envelope_options(Some(2), 1024)?;
+    assert_eq!(alkane.data_input(), Some(2));
+    assert_eq!(alkane.data_max_len(), 1024);
+    alkane.check_data_len(1024)?;
+    assert!(alkane.check_data_len(1025).is_err());
+
+    // Input 0 stays distinguishable from scanning
+    alkane.set_data_envelope_options(Some(0), 0)?;
+    assert_eq!(alkane.data_input(), Some(0));
+
+    // The last input can't be stored plus one and is refused
+    assert!(alkane.set_data_envelope_options(Some(u32::MAX), 0).is_err());
+    assert_eq!(alkane.data_input(), Some(0));
+
+    // A transaction without envelopes has no payload
+    let tx = tx_with_envelopes(&[None]);
+    assert_eq!(envelope_payload(&tx, None), None);
+    assert_eq!(envelope_payload(&tx, Some(0)), None);
+
+    // By default the first envelope of any input is used
+    let tx = tx_with_envelopes(&[None, Some(&b"first"[..]), Some(&b"second!"[..])]);
+    alkane.set_data_envelope_options(None, 0)?;
+    assert_eq!(alkane.envelope_data(&tx)?, b"first".to_vec());
+
+    // A chosen input is used alone, and an input without one gives no data
+    alkane.set_data_envelope_options(Some(2), 0)?;
+    assert_eq!(alkane.envelope_data(&tx)?, b"second!".to_vec());
+    alkane.set_data_envelope_options(Some(0), 0)?;
+    assert!(alkane.envelope_data(&tx)?.is_empty());
+
+    // Envelopes over the size limit are rejected
+    alkane.set_data_envelope_options(Some(2), 6)?;
+    assert!(alkane.envelope_data(&tx).is_err());
+    alkane.set_data_envelope_options(Some(1), 6)?;
+    assert_eq!(alkane.envelope_data(&tx)?, b"first".to_vec());
+
+    // Config records are passed over when scanning
+    let config = InitConfig::default().encode()?;
+    let tx = tx_with_envelopes(&[Some(&config[..]), Some(&b"data"[..])]);
+    alkane.set_data_envelope_options(None, 0)?;
+    assert_eq!(alkane.envelope_data(&tx)?, b"data".to_vec());
+    assert_eq!(config_payload(&tx), Some(config.clone()));
+
+    // Data that starts with the marker stays data, alone or after a config record
+    let mut data = CONFIG_MAGIC.to_vec();
+    data.extend_from_slice(b" not a config record");
+    let tx = tx_with_envelopes(&[Some(&data[..])]);
+    assert_eq!(config_payload(&tx), None);
+    assert_eq!(alkane.envelope_data(&tx)?, data);
+    let tx = tx_with_envelopes(&[Some(&config[..]), Some(&data[..])]);
+    assert_eq!(config_payload(&tx), Some(config.clone()));
+    assert_eq!(alkane.envelope_data(&tx)?, data);
+
+    // Only the first envelope is read as a config record
+    let tx = tx_with_envelopes(&[Some(&b"data"[..]), Some(&config[..])]);
+    assert_eq!(config_payload(&tx), None);
+    assert_eq!(alkane.envelope_data(&tx)?, b"data".to_vec());
+
+    Ok(())
+}
+
+// Wrap bytes for a script push
+fn push_bytes(bytes: &[u8]) -> bitcoin::script::PushBytesBuf {
+    bitcoin::script::PushBytesBuf::try_from(bytes.to_vec()).unwrap()
+}
+
+// Build a transaction with one input per entry, carrying an envelope with
+// the given payload in its tapscript witness
+fn tx_with_envelopes(payloads: &[Option<&[u8]>]) -> bitcoin::Transaction {
+    let input = payloads
+        .iter()
+        .map(|payload| {
+            let mut txin = bitcoin::TxIn::default();
+            if let Some(payload) = payload {
+                let script = bitcoin::script::Builder::new()
+                    .push_opcode(bitcoin::opcodes::OP_FALSE)
+                    .push_opcode(bitcoin::opcodes::all::OP_IF)
+                    .push_slice(push_bytes(b"BIN"))
+                    .push_slice(push_bytes(&[]))
+                    .push_slice(push_bytes(payload))
+                    .push_opcode(bitcoin::opcodes::all::OP_ENDIF)
+                    .into_script();
+                txin.witness = bitcoin::Witness::from_slice(&[script.into_bytes(), vec![0xc0; 33]]);
+            }
+            txin
+        })
+        .collect();
+    bitcoin::Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input,
+        output: vec![],
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_init_config_round_trip() -> Result<()> {
+    let config = InitConfig {
+        admin_count: Some(3),
+        admin_threshold: Some(2),
+        timelock_delay: Some(144),
+        decimals: Some(0),
+        content_type: Some(String::from("image/webp")),
+        data_size: Some(250_000),
+        data_hash: Some([5u8; 32]),
+        name: Some(String::from("A name far longer than the two fixed name words allow")),
+        symbol: Some(String::from("LONGSYM")),
+        data_input: Some(1),
+        max_data_len: Some(400_000),
+        draft: true,
+    };
+    let bytes = config.encode()?;
+    assert!(is_config(&bytes));
+    assert_eq!(InitConfig::decode(&bytes)?, config);
+
+    // An empty record leaves every option to the trailing inputs
+    let empty = InitConfig::default().encode()?;
+    assert_eq!(empty.len(), CONFIG_MAGIC.len() + 1);
+    assert_eq!(InitConfig::decode(&empty)?, InitConfig::default());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_init_config_validation() -> Result<()> {
+    let base = InitConfig {
+        decimals: Some(2),
+        ..Default::default()
+    }
+    .encode()?;
+
+    // Unknown optional tags are skipped, unknown required tags rejected
+    let mut optional = base.clone();
+    optional.extend_from_slice(&[TAG_OPTIONAL | 0x10, 2, 0, 0xaa, 0xbb]);
+    assert_eq!(InitConfig::decode(&optional)?.decimals, Some(2));
+    let mut required = base.clone();
+    required.extend_from_slice(&[0x10, 0, 0]);
+    assert!(InitConfig::decode(&required).is_err());
+
+    // Repeated tags, wrong value sizes and truncated entries are rejected
+    let mut repeated = base.clone();
+    repeated.extend_from_slice(&[TAG_DECIMALS, 1, 0, 4]);
+    assert!(InitConfig::decode(&repeated).is_err());
+    let mut wide = CONFIG_MAGIC.to_vec();
+    wide.extend_from_slice(&[1, TAG_DECIMALS, 2, 0, 4, 0]);
+    assert!(InitConfig::decode(&wide).is_err());
+    assert!(InitConfig::decode(&base[..base.len() - 1]).is_err());
+
+    // Bad magic or version, and a data size without its hash
+    assert!(InitConfig::decode(b"FMCX\x01").is_err());
+    assert!(InitConfig::decode(b"FMCF\x02").is_err());
+    let unpaired = InitConfig {
+        data_size: Some(10),
+        ..Default::default()
+    };
+    assert!(InitConfig::decode(&unpaired.encode()?).is_err());
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_launch_lifecycle() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    // Deployments without a stored state are Live
+    let alkane = MintableAlkane::default();
+    assert_eq!(alkane.launch_state(100)?, LaunchState::Live);
+    alkane.require_state(&[LaunchState::Live], 100)?;
+
+    assert!(!alkane.token_info(100).paused);
+
+    // Draft refuses mints and can only be scheduled for a later height
+    alkane.store_state(LaunchState::Draft, 0);
+    assert!(alkane.require_state(&[LaunchState::Live], 100).is_err());
+    assert!(alkane.token_info(100).paused);
+    alkane.require_state(OPEN_STATES, 100)?;
+    assert!(alkane.schedule_go_live(100, 100).is_err());
+    alkane.schedule_go_live(150, 100)?;
+    assert_eq!(alkane.go_live_height()?, 150);
+
+    // Scheduled turns Live at the go-live height and can't be rescheduled
+    assert_eq!(alkane.launch_state(149)?, LaunchState::Scheduled);
+    assert!(alkane.token_info(149).paused);
+    assert_eq!(alkane.launch_state(150)?, LaunchState::Live);
+    assert!(!alkane.token_info(150).paused);
+    assert!(alkane.schedule_go_live(200, 120).is_err());
+
+    // Closed is terminal
+    alkane.close_lifecycle(160)?;
+    assert_eq!(alkane.launch_state(1_000)?, LaunchState::Closed);
+    assert!(alkane.require_state(OPEN_STATES, 1_000).is_err());
+    alkane.require_state(ANY_STATE, 1_000)?;
+    assert!(alkane.close_lifecycle(1_000).is_err());
+    assert!(alkane.token_info(1_000).paused);
+
+    // Views are served in every state
+    for state in ANY_STATE {
+        alkane.store_state(*state, 150);
+        alkane.require_state(VIEW_STATES, 1_000)?;
+    }
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_draft_changes_apply_at_once() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let admin = AlkaneId::new(2, 10);
+    alkane.add_admin_token(&admin);
+    alkane.set_timelock_delay(10)?;
+    alkane.set_value_per_mint(10);
+    let call = |value: u128| {
+        context_with(
+            vec![80, ACTION_SET_VALUE_PER_MINT, value],
+            vec![AlkaneTransfer { id: admin, value: 1 }],
+        )
+    };
+
+    // In Draft the change applies in the same call, with nothing queued
+    alkane.store_state(LaunchState::Draft, 0);
+    let response = alkane.queue_change_with(&call(25), ACTION_SET_VALUE_PER_MINT, 25, 100)?;
+    assert_eq!(alkane.value_per_mint(), 25);
+    assert_eq!(alkane.queued_change_count(), 0);
+    assert_eq!(
+        decode_events(&response.data)?.1,
+        vec![Event::ParamsChanged {
+            action: ACTION_SET_VALUE_PER_MINT,
+            value: 25
+        }]
+    );
+
+    // Once scheduled, changes wait on the timelock again
+    alkane.schedule_go_live(150, 100)?;
+    alkane.queue_change_with(&call(30), ACTION_SET_VALUE_PER_MINT, 30, 101)?;
+    assert_eq!(alkane.value_per_mint(), 25);
+    assert_eq!(alkane.pending_changes(), vec![0]);
+    assert_eq!(alkane.queued_change(0)?.eta, 111);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_mint_opcodes_refused_outside_live() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    alkane.set_value_per_mint(10);
+    alkane.set_cap(100);
+    let call = context_with(vec![77], vec![]);
+
+    for state in [LaunchState::Draft, LaunchState::Closed] {
+        alkane.store_state(state, 0);
+        let expected = format!("not allowed while the token is {:?}", state);
+
+        // Both mint paths stop at the state check, before touching the transaction
+        let err = alkane.mint_tokens_with(&call, 100).err().unwrap();
+        assert_eq!(err.to_string(), expected);
+        let err = alkane.claim_airdrop_with(&call, 60, 100).err().unwrap();
+        assert_eq!(err.to_string(), expected);
+    }
+    assert_eq!(alkane.minted(), 0);
+    assert_eq!(alkane.total_supply(), 0);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_minter_allowance() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let minter = AlkaneId::new(2, 7);
+
+    // Unlisted callers cannot mint and cannot be revoked
+    assert!(alkane.use_minter_allowance(&minter, 1).is_err());
+    assert!(alkane.set_minter_allowance(&minter, 0).is_err());
+
+    // Listed minters mint any amount within what is left of the allowance
+    alkane.set_minter_allowance(&minter, 100)?;
+    assert_eq!(alkane.minter_ids(), vec![minter]);
+    alkane.use_minter_allowance(&minter, 60)?;
+    alkane.use_minter_allowance(&minter, 30)?;
+    assert_eq!(alkane.minter_used(&minter), 90);
+    assert_eq!(alkane.minter_remaining(&minter), 10);
+    assert!(alkane.use_minter_allowance(&minter, 11).is_err());
+    assert!(alkane.use_minter_allowance(&minter, 0).is_err());
+
+    // Lowering the allowance below the usage stops the minter, revoking keeps the usage
+    alkane.set_minter_allowance(&minter, 50)?;
+    assert_eq!(alkane.minter_remaining(&minter), 0);
+    alkane.set_minter_allowance(&minter, 0)?;
+    assert!(alkane.use_minter_allowance(&minter, 1).is_err());
+    assert_eq!(alkane.minter_used(&minter), 90);
+    assert_eq!(alkane.minter_ids().len(), 1);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_mint_to_checks_caller_and_state() -> Result<()> {
+    // Reset storage
+    reset_test_storage();
+
+    let alkane = MintableAlkane::default();
+    let minter = AlkaneId::new(2, 7);
+    alkane.set_minter_allowance(&minter, 100)?;
+    alkane.store_state(LaunchState::Live, 0);
+
+    // The caller, not an input, decides who is minting
+    let mut stranger = context_with(vec![76, 10], vec![]);
+    stranger.caller = AlkaneId::new(2, 8);
+    let err = alkane.mint_to_with(&stranger, 10, 100).err().unwrap();
+    assert_eq!(err.to_string(), "caller 2:8 is not an approved minter");
+    assert_eq!(alkane.total_supply(), 0);
+
+    // A listed caller gets the tokens back, and the supply is checkpointed
+    let mut call = context_with(vec![76, 40], vec![]);
+    call.caller = minter;
+    let response = alkane.mint_to_with(&call, 40, 100)?;
+    assert_eq!(response.alkanes.0[0].id, call.myself);
+    assert_eq!(response.alkanes.0[0].value, 40);
+    assert_eq!(alkane.total_supply(), 40);
+    assert_eq!(alkane.minter_used(&minter), 40);
+    let supply = CheckpointList::from_keyword("/checkpoints/supply");
+    assert_eq!(supply.value_at(100)?, 40);
+
+    // The delegated mint is in the ledger, keyed by the minter's id
+    assert_eq!(alkane.mint_ledger_count(), 1);
+    let entries = LedgerEntry::decode_range(&alkane.mint_ledger_range(0, 10))?;
+    assert_eq!(entries[0].amount, 40);
+    assert_eq!(entries[0].height, 100);
+    assert_eq!(entries[0].recipient_script_hash, minter_recipient_hash(&minter));
+
+    // Outside Live, or once finalized, nothing is charged or minted
+    for state in [LaunchState::Draft, LaunchState::Closed] {
+        alkane.store_state(state, 0);
+        let err = alkane.mint_to_with(&call, 10, 101).err().unwrap();
+        assert_eq!(err.to_string(), format!("not allowed while the token is {:?}", state));
+    }
+    alkane.store_state(LaunchState::Live, 0);
+    alkane.set_finalization(101, &Txid::all_zeros());
+    let err = alkane.mint_to_with(&call, 10, 101).err().unwrap();
+    assert_eq!(err.to_string(), "mint finalized");
+    assert_eq!(alkane.total_supply(), 40);
+    assert_eq!(alkane.minter_used(&minter), 40);
+    assert_eq!(alkane.mint_ledger_count(), 1);
+
+    Ok(())
+}
+
+#[wasm_bindgen_test]
+fn test_recipient_follows_protostone_pointer() -> Result<()> {
+    let output = |script: bitcoin::ScriptBuf| bitcoin::TxOut {
+        value: bitcoin::Amount::from_sat(546),
+        script_pubkey: script,
+    };
+    let first = bitcoin::ScriptBuf::from_bytes(vec![0x51]);
+    let second = bitcoin::ScriptBuf::from_bytes(vec![0x52]);
+    let runestone = Runestone {
+        protocol: Some(
+            vec![Protostone {
+                burn: None,
+                message: vec![],
+                edicts: vec![],
+                refund: Some(1),
+                pointer: Some(1),
+                from: None,
+                protocol_tag: 1,
+            }]
+            .encipher()?,
+        ),
+        ..Default::default()
+    }
+    .encipher();
+    let mut tx = bitcoin::Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input: vec![bitcoin::TxIn::default()],
+        output: vec![output(first.clone()), output(second.clone()), output(runestone)],
+    };
+
+    // The protostone at virtual output 4 sends its tokens to output 1
+    assert_eq!(recipient_script(&tx, 4), Some(second.as_script()));
+
+    // Other calls fall back to the first non-OP_RETURN output
+    assert_eq!(recipient_script(&tx, 0), Some(first.as_script()));
+    tx.output.pop();
+    assert_eq!(recipient_script(&tx, 3), Some(first.as_script()));
+
+    Ok(())
+}
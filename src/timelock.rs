@@ -0,0 +1,180 @@
+//! Timelocked admin changes
+//!
+//! Parameter changes are queued by the admins with an earliest execution
+//! height, can be cancelled by the admins while pending, and can be executed
+//! by anyone once the delay has passed. The delay is set at initialization
+//! and can only be increased.
+
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// Change the value per mint
+pub const ACTION_SET_VALUE_PER_MINT: u128 = 1;
+/// Change the mint cap (0 for unlimited)
+pub const ACTION_SET_CAP: u128 = 2;
+/// Increase the timelock delay
+pub const ACTION_SET_TIMELOCK_DELAY: u128 = 3;
+
+/// Status of a queued change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeStatus {
+    Pending = 0,
+    Executed = 1,
+    Cancelled = 2,
+}
+
+impl TryFrom<u8> for ChangeStatus {
+    type Error = anyhow::Error;
+
+    fn try_from(v: u8) -> Result<Self> {
+        match v {
+            0 => Ok(ChangeStatus::Pending),
+            1 => Ok(ChangeStatus::Executed),
+            2 => Ok(ChangeStatus::Cancelled),
+            _ => Err(anyhow!("unknown change status {}", v)),
+        }
+    }
+}
+
+/// A queued admin change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuedChange {
+    /// One of the `ACTION_*` constants
+    pub action: u128,
+    /// New value for the parameter
+    pub value: u128,
+    /// Earliest height at which the change can be executed
+    pub eta: u64,
+    /// Current status
+    pub status: ChangeStatus,
+}
+
+impl QueuedChange {
+    /// Encoded size: action (u128) + value (u128) + eta (u64) + status (u8)
+    pub const ENCODED_LEN: usize = 41;
+
+    /// Encode as little-endian fields
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_LEN);
+        bytes.extend_from_slice(&self.action.to_le_bytes());
+        bytes.extend_from_slice(&self.value.to_le_bytes());
+        bytes.extend_from_slice(&self.eta.to_le_bytes());
+        bytes.push(self.status as u8);
+        bytes
+    }
+
+    /// Decode from the layout produced by `encode`
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(anyhow!("invalid queued change length {}", bytes.len()));
+        }
+        Ok(Self {
+            action: u128::from_le_bytes(bytes[0..16].try_into()?),
+            value: u128::from_le_bytes(bytes[16..32].try_into()?),
+            eta: u64::from_le_bytes(bytes[32..40].try_into()?),
+            status: ChangeStatus::try_from(bytes[40])?,
+        })
+    }
+}
+
+/// Timelock trait provides the queue/execute/cancel bookkeeping
+pub trait Timelock: AlkaneResponder {
+    /// Get the pointer to the timelock delay in blocks
+    fn timelock_delay_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/timelock/delay")
+    }
+
+    /// Get the timelock delay in blocks
+    fn timelock_delay(&self) -> u64 {
+        self.timelock_delay_pointer().get_value::<u64>()
+    }
+
+    /// Set the timelock delay, refusing to lower it
+    fn set_timelock_delay(&self, delay: u64) -> Result<()> {
+        if delay < self.timelock_delay() {
+            return Err(anyhow!(
+                "Timelock delay can only be increased: {} < {}",
+                delay,
+                self.timelock_delay()
+            ));
+        }
+        self.timelock_delay_pointer().set_value::<u64>(delay);
+        Ok(())
+    }
+
+    /// Get the pointer to the queued change list
+    fn queued_changes_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/timelock/queue")
+    }
+
+    /// Get the number of changes ever queued
+    fn queued_change_count(&self) -> u32 {
+        self.queued_changes_pointer().length()
+    }
+
+    /// Get a queued change by id
+    fn queued_change(&self, id: u32) -> Result<QueuedChange> {
+        if id >= self.queued_change_count() {
+            return Err(anyhow!("unknown queued change {}", id));
+        }
+        QueuedChange::decode(&self.queued_changes_pointer().select_index(id).get())
+    }
+
+    /// Queue a change executable from `height + delay`, returning its id
+    fn enqueue_change(&self, action: u128, value: u128, height: u64) -> Result<u32> {
+        let eta = height
+            .checked_add(self.timelock_delay())
+            .ok_or_else(|| anyhow!("timelock eta overflow"))?;
+        let id = self.queued_change_count();
+        self.queued_changes_pointer().append(Arc::new(
+            QueuedChange {
+                action,
+                value,
+                eta,
+                status: ChangeStatus::Pending,
+            }
+            .encode(),
+        ));
+        Ok(id)
+    }
+
+    /// Move a pending change to its final status
+    fn close_change(&self, id: u32, status: ChangeStatus) -> Result<QueuedChange> {
+        let mut change = self.queued_change(id)?;
+        if change.status != ChangeStatus::Pending {
+            return Err(anyhow!("queued change {} is not pending", id));
+        }
+        change.status = status;
+        self.queued_changes_pointer()
+            .select_index(id)
+            .set(Arc::new(change.encode()));
+        Ok(change)
+    }
+
+    /// Mark a pending change executed once its eta has been reached
+    fn take_executable_change(&self, id: u32, height: u64) -> Result<QueuedChange> {
+        let change = self.queued_change(id)?;
+        if height < change.eta {
+            return Err(anyhow!(
+                "queued change {} is timelocked until height {}",
+                id,
+                change.eta
+            ));
+        }
+        self.close_change(id, ChangeStatus::Executed)
+    }
+
+    /// Get the ids of all pending changes
+    fn pending_changes(&self) -> Vec<u32> {
+        (0..self.queued_change_count())
+            .filter(|id| {
+                self.queued_change(*id)
+                    .map(|change| change.status == ChangeStatus::Pending)
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,180 @@
+//! Bundled token information served by `GetTokenInfo`
+//!
+//! The binary layout is versioned so indexers can decode responses from
+//! any deployment. All integers are little-endian:
+//!
+//! ```text
+//! version        u8
+//! name           u32 length + UTF-8 bytes
+//! symbol         u32 length + UTF-8 bytes
+//! total_supply   u128
+//! cap            u128
+//! minted         u128
+//! value_per_mint u128
+//! decimals       u8
+//! flags          u8   (bit 0 paused, bit 1 finalized, bit 2 attributes frozen)
+//! data_len       u64  (decompressed length of the token data)
+//! attributes     u32 count, then per attribute a u32 length + UTF-8 key
+//!                and a u32 length + value bytes (version 2 and later)
+//! ```
+
+use crate::codec::Reader;
+use anyhow::{anyhow, Result};
+
+/// Current version of the binary layout
+pub const TOKEN_INFO_VERSION: u8 = 2;
+
+/// Flag bit set while minting is paused
+pub const FLAG_PAUSED: u8 = 0x01;
+/// Flag bit set once minting has been finalized
+pub const FLAG_FINALIZED: u8 = 0x02;
+/// Flag bit set once the metadata attributes have been frozen
+pub const FLAG_ATTRIBUTES_FROZEN: u8 = 0x04;
+
+/// All token state in one structure
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub name: String,
+    pub symbol: String,
+    pub total_supply: u128,
+    pub cap: u128,
+    pub minted: u128,
+    pub value_per_mint: u128,
+    pub decimals: u8,
+    pub paused: bool,
+    pub finalized: bool,
+    pub attributes_frozen: bool,
+    pub data_len: u64,
+    pub attributes: Vec<(String, Vec<u8>)>,
+}
+
+impl TokenInfo {
+    /// Encode in the current binary layout
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![TOKEN_INFO_VERSION];
+        put_string(&mut bytes, &self.name);
+        put_string(&mut bytes, &self.symbol);
+        bytes.extend_from_slice(&self.total_supply.to_le_bytes());
+        bytes.extend_from_slice(&self.cap.to_le_bytes());
+        bytes.extend_from_slice(&self.minted.to_le_bytes());
+        bytes.extend_from_slice(&self.value_per_mint.to_le_bytes());
+        bytes.push(self.decimals);
+        let mut flags = 0u8;
+        if self.paused {
+            flags |= FLAG_PAUSED;
+        }
+        if self.finalized {
+            flags |= FLAG_FINALIZED;
+        }
+        if self.attributes_frozen {
+            flags |= FLAG_ATTRIBUTES_FROZEN;
+        }
+        bytes.push(flags);
+        bytes.extend_from_slice(&self.data_len.to_le_bytes());
+        bytes.extend_from_slice(&(self.attributes.len() as u32).to_le_bytes());
+        for (key, value) in &self.attributes {
+            put_string(&mut bytes, key);
+            bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(value);
+        }
+        bytes
+    }
+
+    /// Decode a `GetTokenInfo` response of any version
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let version = reader.u8()?;
+        if version == 0 || version > TOKEN_INFO_VERSION {
+            return Err(anyhow!("unsupported token info version {}", version));
+        }
+        let name = reader.string()?;
+        let symbol = reader.string()?;
+        let total_supply = reader.u128()?;
+        let cap = reader.u128()?;
+        let minted = reader.u128()?;
+        let value_per_mint = reader.u128()?;
+        let decimals = reader.u8()?;
+        let flags = reader.u8()?;
+        let data_len = reader.u64()?;
+        let mut attributes = Vec::new();
+        if version >= 2 {
+            for _ in 0..reader.u32()? {
+                let key = reader.string()?;
+                let len = reader.u32()? as usize;
+                attributes.push((key, reader.take(len)?.to_vec()));
+            }
+        }
+        Ok(Self {
+            name,
+            symbol,
+            total_supply,
+            cap,
+            minted,
+            value_per_mint,
+            decimals,
+            paused: flags & FLAG_PAUSED != 0,
+            finalized: flags & FLAG_FINALIZED != 0,
+            attributes_frozen: flags & FLAG_ATTRIBUTES_FROZEN != 0,
+            data_len,
+            attributes,
+        })
+    }
+
+    /// Render as a JSON object; u128 values are strings to survive JSON number
+    /// precision, and attribute values are rendered as (lossy) UTF-8 strings
+    pub fn to_json(&self) -> String {
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}:{}",
+                    json_string(key),
+                    json_string(&String::from_utf8_lossy(value))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"version\":{},\"name\":{},\"symbol\":{},\"total_supply\":\"{}\",\"cap\":\"{}\",\"minted\":\"{}\",\"value_per_mint\":\"{}\",\"decimals\":{},\"paused\":{},\"finalized\":{},\"attributes_frozen\":{},\"data_len\":{},\"attributes\":{{{}}}}}",
+            TOKEN_INFO_VERSION,
+            json_string(&self.name),
+            json_string(&self.symbol),
+            self.total_supply,
+            self.cap,
+            self.minted,
+            self.value_per_mint,
+            self.decimals,
+            self.paused,
+            self.finalized,
+            self.attributes_frozen,
+            self.data_len,
+            attributes
+        )
+    }
+}
+
+/// Append a u32 length-prefixed string
+fn put_string(bytes: &mut Vec<u8>, s: &str) {
+    bytes.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+/// Quote and escape a string for JSON output
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,68 @@
+//! Treasury accounting for alkanes held by the contract
+//!
+//! Balances are tracked per AlkaneId so collected proceeds can be withdrawn
+//! by the admins. The books are checked against the contract's actual
+//! balance on every withdrawal.
+
+use crate::alkane_id_from_bytes;
+use alkanes_runtime::runtime::AlkaneResponder;
+use alkanes_runtime::storage::StoragePointer;
+use alkanes_support::id::AlkaneId;
+use alkanes_support::utils::overflow_error;
+use anyhow::{anyhow, Result};
+use metashrew_support::index_pointer::KeyValuePointer;
+use std::sync::Arc;
+
+/// Treasury trait provides per-AlkaneId balance bookkeeping
+pub trait Treasury: AlkaneResponder {
+    /// Get the pointer to the list of AlkaneIds the treasury has held
+    fn treasury_ids_pointer(&self) -> StoragePointer {
+        StoragePointer::from_keyword("/treasury/ids")
+    }
+
+    /// Get the AlkaneIds the treasury has held
+    fn treasury_ids(&self) -> Vec<AlkaneId> {
+        let pointer = self.treasury_ids_pointer();
+        (0..pointer.length())
+            .filter_map(|i| alkane_id_from_bytes(&pointer.select_index(i).get()).ok())
+            .collect()
+    }
+
+    /// Get the pointer to the recorded balance of an AlkaneId
+    fn treasury_balance_pointer(&self, id: &AlkaneId) -> StoragePointer {
+        let bytes: Vec<u8> = id.clone().into();
+        StoragePointer::from_keyword("/treasury/balances/").select(&bytes)
+    }
+
+    /// Get the recorded balance of an AlkaneId
+    fn treasury_balance(&self, id: &AlkaneId) -> u128 {
+        self.treasury_balance_pointer(id).get_value::<u128>()
+    }
+
+    /// Record alkanes kept by the contract
+    fn credit_treasury(&self, id: &AlkaneId, amount: u128) -> Result<()> {
+        let mut pointer = self.treasury_balance_pointer(id);
+        if pointer.get().len() == 0 {
+            let bytes: Vec<u8> = id.clone().into();
+            self.treasury_ids_pointer().append(Arc::new(bytes));
+        }
+        let balance = overflow_error(pointer.get_value::<u128>().checked_add(amount))
+            .map_err(|_| anyhow!("treasury balance overflow"))?;
+        pointer.set_value::<u128>(balance);
+        Ok(())
+    }
+
+    /// Record alkanes leaving the contract
+    fn debit_treasury(&self, id: &AlkaneId, amount: u128) -> Result<()> {
+        let mut pointer = self.treasury_balance_pointer(id);
+        let balance = pointer.get_value::<u128>().checked_sub(amount).ok_or_else(|| {
+            anyhow!(
+                "Insufficient treasury balance: {} of {}",
+                amount,
+                pointer.get_value::<u128>()
+            )
+        })?;
+        pointer.set_value::<u128>(balance);
+        Ok(())
+    }
+}